@@ -0,0 +1,224 @@
+// `#[derive(AocParse)]` for structs that are parsed from a record-style line, such as
+// `Card 1: 41 48 | 83 86` or `{x=787,m=2655,a=1222,s=2876}`. The generated code uses the
+// `parsing` module of the crate it is used in, so it only works inside this workspace.
+//
+// `include_fixture!` and `include_sidecar!` include the example fixture files of `define_examples!`
+// at compile time, so a missing file is a compile error instead of an example that is skipped.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use std::path::{Path, PathBuf};
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Fields, GenericArgument, LitStr,
+    PathArguments, Type,
+};
+
+/// The `#[aoc(...)]` attributes of a struct or a field
+#[derive(Default)]
+struct Attributes {
+    /// Literal text before the struct or field
+    prefix: Option<String>,
+    /// Literal text after the struct or field
+    suffix: Option<String>,
+    /// For a struct, the text between its fields. For a `Vec` field, the text between its
+    /// elements, one per level of nesting. Whitespace is the default.
+    separators: Vec<String>,
+}
+
+impl Attributes {
+    fn parse(attributes: &[Attribute]) -> syn::Result<Attributes> {
+        let mut result = Attributes::default();
+        for attribute in attributes.iter().filter(|a| a.path().is_ident("aoc")) {
+            attribute.parse_nested_meta(|meta| {
+                let value = meta.value()?.parse::<LitStr>()?.value();
+                if meta.path.is_ident("prefix") {
+                    result.prefix = Some(value);
+                } else if meta.path.is_ident("suffix") {
+                    result.suffix = Some(value);
+                } else if meta.path.is_ident("separator") {
+                    result.separators.push(value);
+                } else {
+                    return Err(meta.error("expected `prefix`, `suffix` or `separator`"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(result)
+    }
+}
+
+/// Parse `text`, with optional whitespace before it
+fn literal(text: &Option<String>) -> TokenStream2 {
+    match text {
+        Some(text) => quote! {
+            let (input, _) = ::nom::character::complete::space0(input)?;
+            let (input, _) = ::nom::bytes::complete::tag(#text)(input)?;
+        },
+        None => quote! {},
+    }
+}
+
+/// The element type of `ty`, if it is a `Vec`
+fn vec_element(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Vec" {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) => match arguments.args.first()? {
+            GenericArgument::Type(element) => Some(element),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// A parser for a value of type `ty`: a `Vec` is a list of elements separated by the first of
+/// `separators`, anything else is parsed as a `Record`
+fn value_parser(ty: &Type, separators: &[String]) -> TokenStream2 {
+    match vec_element(ty) {
+        Some(element) => {
+            let element = value_parser(element, separators.get(1..).unwrap_or_default());
+            let separator = match separators.first() {
+                Some(separator) => quote! {
+                    ::nom::sequence::tuple((
+                        ::nom::character::complete::space0,
+                        ::nom::bytes::complete::tag(#separator),
+                        ::nom::character::complete::space0,
+                    ))
+                },
+                None => quote! { ::nom::character::complete::space1 },
+            };
+            quote! { ::nom::multi::separated_list1(#separator, #element) }
+        }
+        None => quote! { <#ty as crate::parsing::Record>::parse_record },
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            name,
+            "AocParse can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            name,
+            "AocParse needs a struct with named fields",
+        ));
+    };
+    let attributes = Attributes::parse(&input.attrs)?;
+    let field_separator = attributes.separators.first().cloned();
+
+    let mut steps = Vec::new();
+    let mut names = Vec::new();
+    for (i, field) in fields.named.iter().enumerate() {
+        let field_name = field.ident.as_ref().unwrap();
+        let field_attributes = Attributes::parse(&field.attrs)?;
+        if i > 0 {
+            steps.push(literal(&field_separator));
+        }
+        let prefix = literal(&field_attributes.prefix);
+        let parser = value_parser(&field.ty, &field_attributes.separators);
+        let suffix = literal(&field_attributes.suffix);
+        steps.push(quote! {
+            #prefix
+            let (input, _) = ::nom::character::complete::space0(input)?;
+            let (input, #field_name) = #parser(input)?;
+            #suffix
+        });
+        names.push(field_name);
+    }
+    let prefix = literal(&attributes.prefix);
+    let suffix = literal(&attributes.suffix);
+
+    Ok(quote! {
+        impl crate::parsing::Record for #name {
+            fn parse_record(input: &str) -> ::nom::IResult<&str, Self> {
+                #prefix
+                #(#steps)*
+                #suffix
+                Ok((input, #name { #(#names),* }))
+            }
+        }
+
+        impl crate::solution::Parse for #name {
+            fn parse(
+                input: crate::solution::Input,
+            ) -> Result<Self, crate::parsing::ParseError> {
+                crate::parsing::parse_all(&input, <Self as crate::parsing::Record>::parse_record)
+            }
+        }
+    })
+}
+
+/// Derive `Record` and `Parse` for a struct with named fields, which are parsed in order. A field
+/// is parsed as a `Record`, or as a list of them if it is a `Vec`, and may be surrounded by
+/// whitespace. Customize it with `#[aoc(...)]` on the struct or its fields:
+/// - `prefix = "..."` and `suffix = "..."`: literal text before and after
+/// - `separator = "..."`: on the struct, the text between fields; on a `Vec` field, the text
+///   between elements, repeated for nested lists from the outside in
+///
+/// ```ignore
+/// #[derive(AocParse)]
+/// #[aoc(prefix = "{", separator = ",", suffix = "}")]
+/// struct Part {
+///     #[aoc(prefix = "x=")]
+///     x: Int,
+///     ...
+/// }
+/// ```
+#[proc_macro_derive(AocParse, attributes(aoc))]
+pub fn derive_aoc_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Find `path` relative to the root of the crate being compiled, which is the workspace root for
+/// the days, or `None` if there is no such file
+fn find_file(path: &Path) -> Option<PathBuf> {
+    let file = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").ok()?).join(path);
+    file.is_file().then_some(file)
+}
+
+/// `include_str!` the file at `path`, or a compile error at `literal` if there is none
+fn include(literal: &LitStr, path: &Path, what: &str) -> TokenStream {
+    match find_file(path) {
+        Some(file) => {
+            let file = file.to_string_lossy();
+            quote!(include_str!(#file)).into()
+        }
+        None => {
+            let message = format!("No {} file: '{}'.", what, path.display());
+            syn::Error::new_spanned(literal, message)
+                .into_compile_error()
+                .into()
+        }
+    }
+}
+
+/// Include an example fixture file, relative to the crate root, as a `&'static str`:
+/// `include_fixture!("examples/2023/05_a.txt")`
+#[proc_macro]
+pub fn include_fixture(input: TokenStream) -> TokenStream {
+    let literal = parse_macro_input!(input as LitStr);
+    include(&literal, Path::new(&literal.value()), "fixture")
+}
+
+/// Include the sidecar file with the expected answers of an example fixture file, which has the
+/// same path with the extension `.expect`: `include_sidecar!("examples/2023/05_a.txt")` includes
+/// `examples/2023/05_a.expect`
+#[proc_macro]
+pub fn include_sidecar(input: TokenStream) -> TokenStream {
+    let literal = parse_macro_input!(input as LitStr);
+    let path = Path::new(&literal.value()).with_extension("expect");
+    include(&literal, &path, "sidecar")
+}
@@ -0,0 +1,14 @@
+// Feed arbitrary bytes to the parse_input of a day, where any panic is a bug: malformed input must
+// be reported as a ParseError. The first byte is the day, the rest is its input, as lossy UTF-8.
+//
+// Run with `cargo +nightly fuzz run parse_input`, see docs/Usage.md.
+#![no_main]
+
+use advent_of_code_2023_fuzz::day_and_input;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Some((solution, input)) = day_and_input(data) {
+        let _ = solution.parse_input(input);
+    }
+});
@@ -0,0 +1,19 @@
+// Run both parts of a day on the inputs that its parse_input accepts, where any panic is a bug:
+// parse_input must reject what the solvers cannot solve. The input is the same as for
+// parse_input: the first byte is the day, the rest is its input, as lossy UTF-8.
+//
+// Run with `cargo +nightly fuzz run solve`, see docs/Usage.md.
+#![no_main]
+
+use advent_of_code_2023_fuzz::day_and_input;
+use aoc::solution::Context;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Some((solution, input)) = day_and_input(data) {
+        if solution.parse_input(input.clone()).is_ok() {
+            let _ = solution.solve_part_one(input.clone(), &Context::puzzle());
+            let _ = solution.solve_part_two(input, &Context::puzzle());
+        }
+    }
+});
@@ -0,0 +1,12 @@
+#!/bin/sh
+# Seed the corpus of each fuzz target with the regressions, prefixed with the byte of their day,
+# as the fuzz targets read them. Run from the repository root.
+set -e
+for file in fuzz/regressions/d*/*.txt; do
+    day=$(basename "$(dirname "$file")" | sed 's/^d0*//')
+    for target in parse_input solve; do
+        mkdir -p "fuzz/corpus/$target"
+        { printf "\\$(printf '%03o' "$day")"; cat "$file"; } \
+            > "fuzz/corpus/$target/d$day-$(basename "$file" .txt)"
+    done
+done
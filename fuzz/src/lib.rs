@@ -0,0 +1,13 @@
+// What the fuzz targets share: how they pick a day and its input from the fuzzed bytes.
+
+use aoc::solution::{Day, Input};
+use aoc::SolutionBox;
+
+/// The day picked by the first byte of `data`, and the rest of it as its input, as lossy UTF-8
+pub fn day_and_input(data: &[u8]) -> Option<(SolutionBox, Input)> {
+    let (&day, input) = data.split_first()?;
+    let solution = aoc::solutions()
+        .into_iter()
+        .find(|s| s.day() == day as Day)?;
+    Some((solution, String::from_utf8_lossy(input).into_owned()))
+}
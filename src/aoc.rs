@@ -1,8 +1,57 @@
+// The days and the helpers they share, as a library for the main binary and the fuzz targets.
+
+// Lets the modules name this crate `aoc`, as the main binary does
+extern crate self as aoc;
+
 use std::{collections::VecDeque, fs::read_to_string};
 
 use serde_json::Value;
 
+pub mod cycle;
+pub mod geometry;
+pub mod grid;
+pub mod interval;
+pub mod line_segment;
 mod macros;
+pub mod math;
+pub mod parsing;
+pub mod path_finding;
+pub mod polynomial;
+pub mod random;
+pub mod solution;
+pub mod y2023;
+
+use solution::*;
+
+pub type SolutionBox = Box<dyn Solution>;
+
+/// All solved days, in order
+pub fn solutions() -> Vec<SolutionBox> {
+    let solutions: Vec<SolutionBox> = vec![
+        y2023::d01::Problem::create_box(),
+        y2023::d02::Problem::create_box(),
+        y2023::d03::Problem::create_box(),
+        y2023::d04::Problem::create_box(),
+        y2023::d05::Problem::create_box(),
+        y2023::d06::Problem::create_box(),
+        y2023::d07::Problem::create_box(),
+        y2023::d08::Problem::create_box(),
+        y2023::d09::Problem::create_box(),
+        y2023::d10::Problem::create_box(),
+        y2023::d11::Problem::create_box(),
+        y2023::d12::Problem::create_box(),
+        y2023::d13::Problem::create_box(),
+        y2023::d14::Problem::create_box(),
+        y2023::d15::Problem::create_box(),
+        y2023::d16::Problem::create_box(),
+        y2023::d18::Problem::create_box(),
+        y2023::d19::Problem::create_box(),
+        y2023::d20::Problem::create_box(),
+        y2023::d21::Problem::create_box(),
+        y2023::d22::Problem::create_box(),
+    ];
+    solutions
+}
 
 /// Default Integer type
 pub type Int = i64;
@@ -13,8 +62,73 @@ pub type Grid<T> = Vec<Vec<T>>;
 /// Default Queue type
 pub type Queue<T> = VecDeque<T>;
 
+/// A type that can be converted from and to a single char, e.g. a cell of a grid in a puzzle input
+pub trait CharConvertable: Sized {
+    /// Convert from a char, or `None` if `c` is invalid
+    fn try_from_char(c: char) -> Option<Self>;
+
+    /// Convert from a char, panics if `c` is invalid
+    fn from_char(c: char) -> Self {
+        Self::try_from_char(c)
+            .unwrap_or_else(|| panic!("Invalid {} char: '{}'.", Self::type_name(), c))
+    }
+
+    /// The name of the type in messages, without its module path, e.g. `Direction`
+    fn type_name() -> &'static str {
+        let name = std::any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name)
+    }
+
+    fn to_char(&self) -> char;
+}
+
+impl CharConvertable for char {
+    fn try_from_char(c: char) -> Option<Self> {
+        Some(c)
+    }
+
+    fn to_char(&self) -> char {
+        *self
+    }
+}
+
+/// Path to the aocf json cache file of a puzzle
+fn get_cache_path(year: i32, day: u32) -> String {
+    format!(".aocf/cache/aoc{}_{:02}.json", year, day)
+}
+
+/// Read a field from the aocf json cache file of a puzzle, without downloading anything
+fn get_cached_field(year: i32, day: u32, field: &str) -> Option<Value> {
+    let json = read_to_string(get_cache_path(year, day)).ok()?;
+    let json_struct: Value = serde_json::from_str(&json).ok()?;
+    match &json_struct[field] {
+        Value::Null => None,
+        value => Some(value.clone()),
+    }
+}
+
+/// Return the title of a puzzle, e.g. "Trebuchet?!", if it is in the aocf cache
+pub fn get_title(year: i32, day: u32) -> Option<String> {
+    match get_cached_field(year, day, "title")? {
+        Value::String(title) => Some(title),
+        _ => None,
+    }
+}
+
+/// Return the most complete puzzle description in the aocf cache, as markdown, if there is one
+pub fn get_description(year: i32, day: u32) -> Option<String> {
+    match get_cached_field(year, day, "brief")? {
+        Value::Object(brief) => brief
+            .values()
+            .filter_map(|part| part.as_str())
+            .max_by_key(|part| part.len())
+            .map(String::from),
+        _ => None,
+    }
+}
+
 pub fn get(year: i32, day: u32) -> String {
-    let aoc_json_cache_path = format!(".aocf/cache/aoc{}_{:02}.json", year, day);
+    let aoc_json_cache_path = get_cache_path(year, day);
     let aoc_json_cache = aocf::Aoc::load_json_from(&aoc_json_cache_path);
     if aoc_json_cache.is_ok() {
         let json_struct: Value =
@@ -23,19 +137,16 @@ pub fn get(year: i32, day: u32) -> String {
         match &json_struct["input"] {
             Value::String(s) => return s.clone(),
             _ => {
-                debug!(
-                    true,
-                    "AoC json file does not contain input field: '{}'.", aoc_json_cache_path
+                log::warn!(
+                    "AoC json file does not contain input field: '{}'.",
+                    aoc_json_cache_path
                 );
             }
         };
     }
 
-    debug!(
-        true,
-        "Not a valid AoC json file: '{}'.", aoc_json_cache_path
-    );
-    debug!(true, "Downloading json file from adventofcode.com.");
+    log::warn!("Not a valid AoC json file: '{}'.", aoc_json_cache_path);
+    log::info!("Downloading json file from adventofcode.com.");
     aocf::Aoc::new()
         .year(Some(year))
         .day(Some(day))
@@ -44,17 +155,3 @@ pub fn get(year: i32, day: u32) -> String {
         .get_input(true)
         .unwrap()
 }
-
-// Return the greatest common multiple of a and b
-pub fn gcd(a: u64, b: u64) -> u64 {
-    if b == 0 {
-        a
-    } else {
-        gcd(b, a % b)
-    }
-}
-
-/// Return the least common multiple of a and b
-pub fn lcm(a: u64, b: u64) -> u64 {
-    a * b / gcd(a, b)
-}
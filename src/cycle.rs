@@ -0,0 +1,136 @@
+// Cycle detection for simulations that repeat a state, so a puzzle asking for the state after a
+// huge number of steps can skip ahead, and an assumption that a sequence is periodic can be
+// checked instead of trusted.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::iter::successors;
+
+/// The shape of a sequence of states that repeats: after `prefix` states, the next `period`
+/// states repeat forever
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Cycle {
+    pub prefix: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The index of the first state equal to state `n`
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.prefix {
+            n
+        } else {
+            self.prefix + (n - self.prefix) % self.period
+        }
+    }
+}
+
+/// Find the cycle in `initial`, `step(initial)`, ... using Brent's algorithm, which only keeps two
+/// states in memory, but calls `step` up to about three times per state
+/// See: https://en.wikipedia.org/wiki/Cycle_detection#Brent's_algorithm
+pub fn find_cycle<T: PartialEq + Clone>(initial: T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    // Find the period, by moving the hare ahead in powers of two
+    let (mut power, mut period) = (1, 1);
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // Find the prefix, with the hare a period ahead of the tortoise
+    let mut prefix = 0;
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+    Cycle { prefix, period }
+}
+
+/// Find the cycle in `initial`, `step(initial)`, ... by remembering every state, which calls
+/// `step` once per state
+pub fn find_cycle_hashed<T: Eq + Hash + Clone>(initial: T, step: impl FnMut(&T) -> T) -> Cycle {
+    find_cycle_in(iterate(initial, step)).unwrap()
+}
+
+/// Find the first repeated state in `states`, or `None` if they run out before repeating
+pub fn find_cycle_in<T: Eq + Hash>(states: impl IntoIterator<Item = T>) -> Option<Cycle> {
+    let mut seen: HashMap<T, usize> = HashMap::new();
+    for (index, state) in states.into_iter().enumerate() {
+        if let Some(prefix) = seen.insert(state, index) {
+            return Some(Cycle {
+                prefix,
+                period: index - prefix,
+            });
+        }
+    }
+    None
+}
+
+/// The state after `n` steps from `initial`, skipping ahead once a state repeats
+pub fn nth_state<T: Eq + Hash + Clone>(initial: T, step: impl FnMut(&T) -> T, n: usize) -> T {
+    let mut states: Vec<T> = Vec::new();
+    let mut seen: HashMap<T, usize> = HashMap::new();
+    for (index, state) in iterate(initial, step).enumerate() {
+        if index == n {
+            return state;
+        }
+        if let Some(&prefix) = seen.get(&state) {
+            let cycle = Cycle {
+                prefix,
+                period: index - prefix,
+            };
+            return states.swap_remove(cycle.reduce(n));
+        }
+        seen.insert(state.clone(), index);
+        states.push(state);
+    }
+    unreachable!()
+}
+
+fn iterate<T>(initial: T, mut step: impl FnMut(&T) -> T) -> impl Iterator<Item = T> {
+    successors(Some(initial), move |state| Some(step(state)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+
+    #[test]
+    fn test_find_cycle() {
+        // Powers of 3 modulo 100 return to 1 after 20 steps
+        let step = |x: &u32| x * 3 % 100;
+        let expected = Cycle {
+            prefix: 0,
+            period: 20,
+        };
+        test!(expected, find_cycle(1, step));
+        test!(expected, find_cycle_hashed(1, step));
+
+        // 0, 1, 2, 3, 4, 5, 6, 2, ...
+        let step = |x: &u32| if *x == 6 { 2 } else { x + 1 };
+        let expected = Cycle {
+            prefix: 2,
+            period: 5,
+        };
+        test!(expected, find_cycle(0, step));
+        test!(expected, find_cycle_hashed(0, step));
+        test!(6, nth_state(0, step, 6));
+        test!(2, nth_state(0, step, 7));
+        test!(5, nth_state(0, step, 1_000_000_000_000));
+        test!(1, expected.reduce(1));
+        test!(None::<Cycle>, find_cycle_in(0..10));
+    }
+}
@@ -0,0 +1,279 @@
+// Pull the example inputs and their expected answers out of a puzzle description, either the
+// markdown in the aocf cache or a puzzle page saved as HTML, and print them as `define_examples!`
+// entries, or write them as fixture files. Descriptions are prose, so the result is a starting
+// point to check, not a guarantee.
+
+use crate::solution::*;
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::Path;
+
+/// Directory containing the fixture files of all examples, as `<year>/<DD>_<letter>.txt`
+static FIXTURES_DIRECTORY: &str = "examples";
+
+/// How code blocks and emphasised inline code are written in a description format
+struct Markup {
+    code_block: (&'static str, &'static str),
+    emphasised_code: [(&'static str, &'static str); 2],
+    decode: fn(&str) -> String,
+}
+
+/// The markdown in the `brief` of the aocf cache, where answers look like `` `*4361*` ``
+static MARKDOWN: Markup = Markup {
+    code_block: ("```\n", "```"),
+    emphasised_code: [("`*", "*`"), ("*`", "`*")],
+    decode: |text| text.to_string(),
+};
+
+/// A puzzle page saved from adventofcode.com, where answers look like `<code><em>4361</em></code>`
+static HTML: Markup = Markup {
+    code_block: ("<pre><code>", "</code></pre>"),
+    emphasised_code: [
+        ("<code><em>", "</em></code>"),
+        ("<em><code>", "</code></em>"),
+    ],
+    decode: decode_html,
+};
+
+/// Heading that separates part one from part two, in both formats
+static PART_TWO_HEADING: &str = "--- Part Two ---";
+
+/// An example input, with the answers the description gives for it
+#[derive(Debug, PartialEq)]
+pub struct ExtractedExample {
+    pub input: String,
+    pub part_one: Option<Answer>,
+    pub part_two: Option<Answer>,
+}
+
+impl ExtractedExample {
+    /// The `Expect` expression for this example, as Rust source
+    fn expect(&self) -> String {
+        match (self.part_one, self.part_two) {
+            (Some(one), Some(two)) => format!("Expect::PartsOneAndTwo({}, {})", one, two),
+            (Some(one), None) => format!("Expect::PartOne({})", one),
+            (None, Some(two)) => format!("Expect::PartTwo({})", two),
+            (None, None) => "Expect::Any".to_string(),
+        }
+    }
+
+    /// The sidecar file for this example, read by `Expect::parse_sidecar`
+    fn sidecar(&self) -> String {
+        let one = self.part_one.map(|one| format!("part_one = {}\n", one));
+        let two = self.part_two.map(|two| format!("part_two = {}\n", two));
+        one.unwrap_or_default() + &two.unwrap_or_default()
+    }
+}
+
+/// Return every piece of `text` between `open` and the next `close`, with its position in `text`
+fn between<'a>(text: &'a str, open: &str, close: &str) -> Vec<(usize, &'a str)> {
+    let mut pieces = Vec::new();
+    let mut position = 0;
+    while let Some(start) = text[position..].find(open) {
+        let start = position + start + open.len();
+        let Some(end) = text[start..].find(close) else {
+            break;
+        };
+        pieces.push((start, &text[start..start + end]));
+        position = start + end + close.len();
+    }
+    pieces
+}
+
+/// Remove the tags from an HTML fragment, and decode the entities the puzzle pages use
+fn decode_html(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Return the first code block of a part, and the last emphasised number, which is the answer
+fn extract_part(part: &str, markup: &Markup) -> (Option<String>, Option<Answer>) {
+    let (open, close) = markup.code_block;
+    let input = between(part, open, close)
+        .first()
+        .map(|(_, block)| (markup.decode)(block).trim_end_matches('\n').to_string());
+    let answer = markup
+        .emphasised_code
+        .iter()
+        .flat_map(|(open, close)| between(part, open, close))
+        .filter_map(|(position, code)| Some((position, code.trim().parse::<Answer>().ok()?)))
+        .max_by_key(|(position, _)| *position)
+        .map(|(_, answer)| answer);
+    (input, answer)
+}
+
+/// Extract the examples of both parts. Part two usually repeats the example of part one, in which
+/// case a single example is returned for both; a part without a code block reuses the previous one.
+fn extract_examples(description: &str, markup: &Markup) -> Vec<ExtractedExample> {
+    let mut examples: Vec<ExtractedExample> = Vec::new();
+    for (part, text) in description.split(PART_TWO_HEADING).take(2).enumerate() {
+        let (input, answer) = extract_part(text, markup);
+        let Some(input) = input.or_else(|| examples.last().map(|e| e.input.clone())) else {
+            continue;
+        };
+        let example = match examples.iter_mut().find(|e| e.input == input) {
+            Some(example) => example,
+            None => {
+                examples.push(ExtractedExample {
+                    input,
+                    part_one: None,
+                    part_two: None,
+                });
+                examples.last_mut().unwrap()
+            }
+        };
+        match part {
+            0 => example.part_one = answer,
+            _ => example.part_two = answer,
+        }
+    }
+    examples
+}
+
+/// Format examples as a `define_examples!` invocation, indented to paste into an `impl Solution`
+fn format_examples(examples: &[ExtractedExample]) -> String {
+    let entries: Vec<String> = examples
+        .iter()
+        .map(|example| {
+            let (open, close) = match (example.input.contains('"'), example.input.contains('\\')) {
+                (true, _) => ("r#\"", "\"#"),
+                (false, true) => ("r\"", "\""),
+                (false, false) => ("\"", "\""),
+            };
+            let lines: String = example
+                .input
+                .lines()
+                .map(|line| match line.is_empty() {
+                    true => "\n".to_string(),
+                    false => format!("            {}\n", line),
+                })
+                .collect();
+            format!(
+                "        (\n            {}\n{}            {},\n            {},\n        )",
+                open,
+                lines,
+                close,
+                example.expect()
+            )
+        })
+        .collect();
+    format!("    define_examples! {{\n{}\n    }}\n", entries.join(",\n"))
+}
+
+/// Write each example to a fixture file with a sidecar file for its answers, and return the
+/// `define_examples!` invocation loading them
+fn write_fixtures(year: Year, day: Day, examples: &[ExtractedExample]) -> String {
+    let directory = Path::new(FIXTURES_DIRECTORY).join(year.to_string());
+    create_dir_all(&directory).unwrap();
+    let entries: Vec<String> = examples
+        .iter()
+        .zip('a'..='z')
+        .map(|(example, letter)| {
+            let path = directory.join(format!("{:02}_{}.txt", day, letter));
+            write(&path, format!("{}\n", example.input)).unwrap();
+            write(path.with_extension("expect"), example.sidecar()).unwrap();
+            println!("Wrote: '{}'.", path.display());
+            format!("        (file(\"{}\"),)", path.display())
+        })
+        .collect();
+    format!("    define_examples! {{\n{}\n    }}\n", entries.join(",\n"))
+}
+
+/// Print the examples of a puzzle as `define_examples!` entries, read from `html` if specified,
+/// and from the aocf cache otherwise. With `fixtures`, write them to fixture files instead.
+pub fn print_examples(year: Year, day: Day, html: Option<&Path>, fixtures: bool) {
+    let examples = match html {
+        Some(path) => {
+            let page = read_to_string(path)
+                .unwrap_or_else(|_| panic!("Cannot read: '{}'.", path.display()));
+            extract_examples(&page, &HTML)
+        }
+        None => {
+            let description = aoc::get_description(year, day).unwrap_or_else(|| {
+                panic!("No cached puzzle description for: {}-{:02}.", year, day)
+            });
+            extract_examples(&description, &MARKDOWN)
+        }
+    };
+    assert!(!examples.is_empty(), "No examples found.");
+    if fixtures {
+        print!("{}", write_fixtures(year, day, &examples));
+        return;
+    }
+    for example in &examples {
+        if example
+            .input
+            .lines()
+            .any(|line| line.starts_with(char::is_whitespace))
+        {
+            println!("Warning: leading whitespace is trimmed from inline examples, use --write.");
+        }
+    }
+    print!("{}", format_examples(&examples));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_markdown() {
+        let description = "Here is an example:\n\n```\n1abc2\ntreb7uchet\n\n```\n\nAdding these together produces `*142*`.\n\n\\--- Part Two ---\n----------\n\nFor example:\n\n```\ntwo1nine\n\n```\n\nAdding these produces `*29*`.\n";
+        let examples = extract_examples(description, &MARKDOWN);
+        test!(
+            vec![
+                ExtractedExample {
+                    input: "1abc2\ntreb7uchet".to_string(),
+                    part_one: Some(142),
+                    part_two: None,
+                },
+                ExtractedExample {
+                    input: "two1nine".to_string(),
+                    part_one: None,
+                    part_two: Some(29),
+                },
+            ],
+            examples
+        );
+    }
+
+    #[test]
+    fn test_extract_html() {
+        let page = "<article class=\"day-desc\"><h2>--- Day 3: Gear Ratios ---</h2><pre><code>467..114..\n...*......\n&lt;.<em>35</em>.\n</code></pre><p>their sum is <code><em>4361</em></code>.</p></article><p>Your puzzle answer was <code>540131</code>.</p><article class=\"day-desc\"><h2 id=\"part2\">--- Part Two ---</h2><p>Consider the same schematic again:</p><pre><code>467..114..\n...*......\n&lt;.<em>35</em>.\n</code></pre><p>Adding up all of the gear ratios produces <em><code>467835</code></em>.</p></article>";
+        let examples = extract_examples(page, &HTML);
+        test!(
+            vec![ExtractedExample {
+                input: "467..114..\n...*......\n<.35.".to_string(),
+                part_one: Some(4361),
+                part_two: Some(467835),
+            }],
+            examples
+        );
+    }
+
+    #[test]
+    fn test_format_examples() {
+        let examples = vec![ExtractedExample {
+            input: "a \"b\"\n\nc".to_string(),
+            part_one: Some(1),
+            part_two: None,
+        }];
+        test!(
+            "    define_examples! {\n        (\n            r#\"\n            a \"b\"\n\n            c\n            \"#,\n            Expect::PartOne(1),\n        )\n    }\n",
+            format_examples(&examples)
+        );
+        test!("part_one = 1\n", examples[0].sidecar());
+    }
+}
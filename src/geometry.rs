@@ -0,0 +1,127 @@
+// Exact integer geometry on polygons of grid Points, e.g. a loop in a maze or a dig plan. A
+// polygon is a slice of its vertices in order, the last vertex is connected to the first.
+
+use crate::grid::Point;
+use crate::math;
+use aoc::Int;
+
+/// Where a Point lies relative to a polygon
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// The edges of a polygon, including the one from the last vertex back to the first
+fn edges(vertices: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(a, b)| (*a, *b))
+}
+
+/// The cross product of `b - a` and `p - a`, positive if `p` is to the left of `a` → `b`, when
+/// y grows upwards
+fn cross(a: Point, b: Point, p: Point) -> Int {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+/// Twice the area of a polygon, using the shoelace formula, which keeps it an exact integer, or
+/// `None` if it overflows
+/// See: https://en.wikipedia.org/wiki/Shoelace_formula
+pub fn double_area(vertices: &[Point]) -> Option<Int> {
+    edges(vertices)
+        .try_fold(0 as Int, |sum, (a, b)| {
+            sum.checked_add(a.x.checked_mul(b.y)?.checked_sub(b.x.checked_mul(a.y)?)?)
+        })?
+        .checked_abs()
+}
+
+/// The number of lattice points on the edges of a polygon, or `None` if it overflows
+pub fn boundary_points(vertices: &[Point]) -> Option<Int> {
+    edges(vertices).try_fold(0 as Int, |sum, (a, b)| {
+        let (dx, dy) = (b.x.checked_sub(a.x)?, b.y.checked_sub(a.y)?);
+        sum.checked_add(Int::try_from(math::gcd(dx.unsigned_abs(), dy.unsigned_abs())).ok()?)
+    })
+}
+
+/// The number of lattice points strictly inside a simple polygon, using Pick's theorem, or `None`
+/// if it overflows
+/// See: https://en.wikipedia.org/wiki/Pick%27s_theorem
+pub fn interior_points(vertices: &[Point]) -> Option<Int> {
+    if vertices.len() < 3 {
+        return Some(0);
+    }
+    // A = i + b/2 - 1, so i = (2A - b + 2) / 2
+    let double_area = double_area(vertices)?;
+    Some(
+        double_area
+            .checked_sub(boundary_points(vertices)?)?
+            .checked_add(2)?
+            / 2,
+    )
+}
+
+/// The number of lattice points inside or on the boundary of a simple polygon, or `None` if it
+/// overflows
+pub fn lattice_points(vertices: &[Point]) -> Option<Int> {
+    interior_points(vertices)?.checked_add(boundary_points(vertices)?)
+}
+
+/// Locate `point` relative to a polygon, using its winding number
+/// See: https://en.wikipedia.org/wiki/Point_in_polygon#Winding_number_algorithm
+pub fn locate(vertices: &[Point], point: &Point) -> Location {
+    let mut winding_number = 0;
+    for (a, b) in edges(vertices) {
+        let side = cross(a, b, *point);
+        let between = |p: Int, q: Int, r: Int| p.min(q) <= r && r <= p.max(q);
+        if side == 0 && between(a.x, b.x, point.x) && between(a.y, b.y, point.y) {
+            return Location::Boundary;
+        }
+        if a.y <= point.y && point.y < b.y && side > 0 {
+            winding_number += 1;
+        } else if b.y <= point.y && point.y < a.y && side < 0 {
+            winding_number -= 1;
+        }
+    }
+    match winding_number {
+        0 => Location::Outside,
+        _ => Location::Inside,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+
+    #[test]
+    fn test_polygon() {
+        let square: Vec<Point> = [(0, 0), (4, 0), (4, 4), (0, 4)]
+            .iter()
+            .map(|&(x, y)| Point::new(x, y))
+            .collect();
+        test!(Some(32), double_area(&square));
+        test!(Some(16), boundary_points(&square));
+        test!(Some(9), interior_points(&square));
+        test!(Some(25), lattice_points(&square));
+        test!(Location::Inside, locate(&square, &Point::new(2, 3)));
+        test!(Location::Boundary, locate(&square, &Point::new(4, 1)));
+        test!(Location::Boundary, locate(&square, &Point::new(0, 0)));
+        test!(Location::Outside, locate(&square, &Point::new(5, 2)));
+        test!(Location::Outside, locate(&square, &Point::new(2, -1)));
+
+        let triangle: Vec<Point> = square.iter().rev().skip(1).cloned().collect();
+        test!(Some(16), double_area(&triangle));
+        test!(Some(12), boundary_points(&triangle));
+        test!(Some(3), interior_points(&triangle));
+        test!(Location::Boundary, locate(&triangle, &Point::new(2, 2)));
+        test!(Location::Inside, locate(&triangle, &Point::new(3, 2)));
+        test!(Location::Outside, locate(&triangle, &Point::new(1, 2)));
+        test!(Some(0), lattice_points(&[]));
+        let huge = [(0, 0), (Int::MAX, 0), (Int::MAX, Int::MAX), (0, Int::MAX)];
+        let huge: Vec<Point> = huge.iter().map(|&(x, y)| Point::new(x, y)).collect();
+        test!(None::<Int>, lattice_points(&huge));
+    }
+}
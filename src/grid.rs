@@ -1,21 +1,40 @@
+// A generic 2D grid, with Points and Directions to move around in it.
+
+use crate::solution::InputLines;
+use aoc::CharConvertable;
 use aoc::Grid;
 use aoc::Int;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fmt::Display;
 
-/// Generic 2D grid with helper functions to move around
-#[derive(Clone)]
+/// Generic 2D grid with helper functions to move around, stored row by row in a single Vec
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Map<T> {
-    pub grid: Grid<T>,
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
 }
 
 impl<T> Map<T> {
+    /// Create a Map of `columns` by `rows`, with every cell set to `value`
+    pub fn new(columns: usize, rows: usize, value: T) -> Map<T>
+    where
+        T: Clone,
+    {
+        Map {
+            cells: vec![value; columns * rows],
+            width: columns,
+            height: rows,
+        }
+    }
+
     pub fn get_rows(&self) -> usize {
-        self.grid.len()
+        self.height
     }
 
     pub fn get_columns(&self) -> usize {
-        self.grid.first().unwrap().len()
+        self.width
     }
 
     pub fn point_within_grid(&self, point: &Point) -> bool {
@@ -26,71 +45,458 @@ impl<T> Map<T> {
         x >= 0 && x < self.get_columns() as Int && y >= 0 && y < self.get_rows() as Int
     }
 
+    /// Index of the cell at (x, y) in `cells`, which must be within the Map
+    fn index_of(&self, x: usize, y: usize) -> usize {
+        assert!(
+            x < self.width && y < self.height,
+            "Invalid point: '({}, {})'.",
+            x,
+            y
+        );
+        y * self.width + x
+    }
+
     pub fn point_get(&self, point: &Point) -> Option<&T> {
         self.get(point.x, point.y)
     }
 
     pub fn get(&self, x: Int, y: Int) -> Option<&T> {
-        return if self.within(x, y) {
-            Some(&self.grid[y as usize][x as usize])
+        if self.within(x, y) {
+            Some(&self[(x as usize, y as usize)])
         } else {
             None
-        };
+        }
+    }
+
+    pub fn point_get_mut(&mut self, point: &Point) -> Option<&mut T> {
+        self.get_mut(point.x, point.y)
+    }
+
+    pub fn get_mut(&mut self, x: Int, y: Int) -> Option<&mut T> {
+        if self.within(x, y) {
+            Some(&mut self[(x as usize, y as usize)])
+        } else {
+            None
+        }
+    }
+
+    /// Set the value at `point`, panics if `point` is outside of the Map
+    pub fn set(&mut self, point: &Point, value: T) {
+        self[*point] = value;
     }
 
     /// Return a list of Points for which the Predicate on T hold
     pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Vec<Point> {
-        let mut result = Vec::new();
-        for y in 0..self.get_rows() {
-            for x in 0..self.get_columns() {
-                let point = Point::new(x as Int, y as Int);
-                let value = self.point_get(&point).unwrap();
-                if predicate(value) {
-                    result.push(point);
-                }
-            }
-        }
-        result
+        self.enumerate()
+            .filter(|(_, value)| predicate(value))
+            .map(|(point, _)| point)
+            .collect()
     }
 
-    /// Returns a list of Points within the Map that are adjacent to point
-    pub fn get_adjacent(&self, point: &Point) -> Vec<Point> {
+    /// Returns a list of Points within the Map that are adjacent to point, including diagonally
+    pub fn get_neighbours(&self, point: &Point) -> Vec<Point> {
         if !self.point_within_grid(point) {
-            Vec::new()
-        } else {
-            vec![North, East, South, West]
-                .iter()
-                .map(|d| point.move_to(d))
-                .filter(|p| self.point_within_grid(p))
-                .collect()
+            return Vec::new();
         }
+        Direction8::iter()
+            .map(|d| d.to_point())
+            .map(|unit| point.translate(unit.x, unit.y))
+            .filter(|p| self.point_within_grid(p))
+            .collect()
+    }
+
+    pub fn row(&self, y: usize) -> impl DoubleEndedIterator<Item = &T> {
+        self[y].iter()
+    }
+
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> {
+        (0..self.height).map(move |y| &self[(x, y)])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &T>> {
+        (0..self.get_rows()).map(|y| self.row(y))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &T>> {
+        (0..self.get_columns()).map(|x| self.column(x))
+    }
+
+    /// Iterate over all cells with their Point, row by row
+    pub fn enumerate(&self) -> impl Iterator<Item = (Point, &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, value)| (Point::new((i % width) as Int, (i / width) as Int), value))
+    }
+
+    /// Return a Map of the same size, with `f` applied to every cell
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Map<U> {
+        Map {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Return the Map mirrored in its diagonal, so rows become columns
+    pub fn transpose(&self) -> Map<T>
+    where
+        T: Clone,
+    {
+        self.columns()
+            .map(|column| column.cloned().collect())
+            .collect()
+    }
+
+    /// Return the Map with its columns in reverse order
+    pub fn flip_horizontal(&self) -> Map<T>
+    where
+        T: Clone,
+    {
+        self.rows()
+            .map(|row| row.rev().cloned().collect())
+            .collect()
+    }
+
+    /// Return the Map with its rows in reverse order
+    pub fn flip_vertical(&self) -> Map<T>
+    where
+        T: Clone,
+    {
+        (0..self.height).rev().map(|y| self[y].to_vec()).collect()
+    }
+
+    pub fn rotate_clockwise(&self) -> Map<T>
+    where
+        T: Clone,
+    {
+        self.flip_vertical().transpose()
+    }
+
+    pub fn rotate_counterclockwise(&self) -> Map<T>
+    where
+        T: Clone,
+    {
+        self.transpose().flip_vertical()
+    }
+
+    /// Return a view of the Map repeated infinitely in every direction, so coordinates wrap around
+    pub fn wrapping(&self) -> WrappingMap<'_, T> {
+        WrappingMap { map: self }
+    }
+
+    /// Return a view of the `columns` by `rows` sub-grid with its top left corner at `origin`,
+    /// panics if it does not fit within the Map
+    pub fn view(&self, origin: Point, columns: usize, rows: usize) -> MapView<'_, T> {
+        let fits =
+            |start: Int, length: usize, size: usize| start >= 0 && start as usize + length <= size;
+        assert!(
+            fits(origin.x, columns, self.get_columns()) && fits(origin.y, rows, self.get_rows()),
+            "Invalid view: {}x{} at '{}'.",
+            columns,
+            rows,
+            origin
+        );
+        MapView {
+            map: self,
+            origin,
+            columns,
+            rows,
+        }
+    }
+}
+
+/// Index a cell by its (x, y) coordinates
+impl<T> Index<(usize, usize)> for Map<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        &self.cells[self.index_of(x, y)]
     }
 }
 
-// Deref and DerefMut allow us to extend a Grid<T> in Grid2D<T>
-impl<T> Deref for Map<T> {
-    type Target = Vec<Vec<T>>;
+impl<T> IndexMut<(usize, usize)> for Map<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
+        let index = self.index_of(x, y);
+        &mut self.cells[index]
+    }
+}
+
+/// Index a cell by its Point, panics if the Point is outside of the Map
+impl<T> Index<Point> for Map<T> {
+    type Output = T;
 
-    fn deref(&self) -> &Self::Target {
-        &self.grid
+    fn index(&self, point: Point) -> &Self::Output {
+        assert!(
+            self.point_within_grid(&point),
+            "Invalid point: '{}'.",
+            point
+        );
+        &self[(point.x as usize, point.y as usize)]
     }
 }
 
-impl<T> DerefMut for Map<T> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.grid
+impl<T> IndexMut<Point> for Map<T> {
+    fn index_mut(&mut self, point: Point) -> &mut Self::Output {
+        assert!(
+            self.point_within_grid(&point),
+            "Invalid point: '{}'.",
+            point
+        );
+        &mut self[(point.x as usize, point.y as usize)]
     }
 }
 
-// Implement FromIterator for Grid2D
+/// Index a row, so `map[y][x]` keeps working like it did for a `Grid<T>`
+impl<T> Index<usize> for Map<T> {
+    type Output = [T];
+
+    fn index(&self, y: usize) -> &Self::Output {
+        assert!(y < self.height, "Invalid row: '{}'.", y);
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+}
+
+impl<T> IndexMut<usize> for Map<T> {
+    fn index_mut(&mut self, y: usize) -> &mut Self::Output {
+        assert!(y < self.height, "Invalid row: '{}'.", y);
+        &mut self.cells[y * self.width..(y + 1) * self.width]
+    }
+}
+
+/// Collect rows into a Map, panics if they are not all of the same length
 impl<T> FromIterator<Vec<T>> for Map<T> {
     fn from_iter<I: IntoIterator<Item = Vec<T>>>(iter: I) -> Self {
-        let grid: Vec<Vec<T>> = iter.into_iter().collect();
-        Map { grid }
+        let mut map = Map {
+            cells: Vec::new(),
+            width: 0,
+            height: 0,
+        };
+        for row in iter {
+            if map.height == 0 {
+                map.width = row.len();
+            }
+            assert!(
+                row.len() == map.width,
+                "Invalid row length: '{}', expected: '{}'.",
+                row.len(),
+                map.width
+            );
+            map.cells.extend(row);
+            map.height += 1;
+        }
+        map
+    }
+}
+
+impl<T> From<Grid<T>> for Map<T> {
+    fn from(grid: Grid<T>) -> Self {
+        grid.into_iter().collect()
+    }
+}
+
+/// Lookups shared by Map, WrappingMap and SparseMap, so algorithms such as a breadth-first
+/// search work on bounded, wrapping and infinite grids alike
+pub trait Lookup<T> {
+    /// Return the cell at `point`, or `None` if `point` is outside of the grid
+    fn point_get(&self, point: &Point) -> Option<&T>;
+
+    /// Returns a list of Points within the grid that are adjacent to point
+    fn get_adjacent(&self, point: &Point) -> Vec<Point> {
+        if self.point_get(point).is_none() {
+            return Vec::new();
+        }
+        Direction::iter()
+            .map(|d| point.move_to(&d))
+            .filter(|p| self.point_get(p).is_some())
+            .collect()
+    }
+}
+
+impl<T> Lookup<T> for Map<T> {
+    fn point_get(&self, point: &Point) -> Option<&T> {
+        self.get(point.x, point.y)
+    }
+}
+
+/// A Map repeated infinitely in every direction, where coordinates are taken modulo its size
+pub struct WrappingMap<'a, T> {
+    map: &'a Map<T>,
+}
+
+impl<T> WrappingMap<'_, T> {
+    /// Return the cell at (x, y), or `None` if the Map is empty
+    pub fn get(&self, x: Int, y: Int) -> Option<&T> {
+        let (columns, rows) = (self.map.get_columns() as Int, self.map.get_rows() as Int);
+        if columns == 0 || rows == 0 {
+            return None;
+        }
+        Some(&self.map[(x.rem_euclid(columns) as usize, y.rem_euclid(rows) as usize)])
+    }
+}
+
+impl<T> Lookup<T> for WrappingMap<'_, T> {
+    fn point_get(&self, point: &Point) -> Option<&T> {
+        self.get(point.x, point.y)
+    }
+}
+
+/// An infinite 2D grid, storing only the cells that differ from its default value
+#[derive(Clone, PartialEq, Eq)]
+pub struct SparseMap<T> {
+    cells: HashMap<Point, T>,
+    default: T,
+}
+
+impl<T> SparseMap<T> {
+    /// Create a SparseMap where every cell is `default`
+    pub fn new(default: T) -> SparseMap<T> {
+        SparseMap {
+            cells: HashMap::new(),
+            default,
+        }
+    }
+
+    /// Create a SparseMap with the cells of `map`, surrounded by `default`
+    pub fn from_map(map: &Map<T>, default: T) -> SparseMap<T>
+    where
+        T: Clone,
+    {
+        let mut sparse_map = SparseMap::new(default);
+        for (point, value) in map.enumerate() {
+            sparse_map.set(&point, value.clone());
+        }
+        sparse_map
+    }
+
+    pub fn get(&self, x: Int, y: Int) -> &T {
+        self.cells.get(&Point::new(x, y)).unwrap_or(&self.default)
+    }
+
+    pub fn set(&mut self, point: &Point, value: T) {
+        self.cells.insert(*point, value);
+    }
+
+    /// Return a list of the set Points for which the Predicate on T hold, in reading order
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Vec<Point> {
+        let mut points: Vec<Point> = self
+            .cells
+            .iter()
+            .filter(|(_, value)| predicate(value))
+            .map(|(point, _)| *point)
+            .collect();
+        points.sort_by(Point::reading_order);
+        points
+    }
+
+    /// Return the top left and bottom right corners of the set cells, `None` if there are none
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        let xs = self.cells.keys().map(|p| p.x);
+        let ys = self.cells.keys().map(|p| p.y);
+        Some((
+            Point::new(xs.clone().min()?, ys.clone().min()?),
+            Point::new(xs.max()?, ys.max()?),
+        ))
+    }
+
+    /// Copy the cells within `bounds` into a Map
+    pub fn to_map(&self) -> Map<T>
+    where
+        T: Clone,
+    {
+        let Some((min, max)) = self.bounds() else {
+            return Map::from_iter(Vec::new());
+        };
+        (min.y..=max.y)
+            .map(|y| (min.x..=max.x).map(|x| self.get(x, y).clone()).collect())
+            .collect()
+    }
+}
+
+impl<T> Lookup<T> for SparseMap<T> {
+    fn point_get(&self, point: &Point) -> Option<&T> {
+        Some(self.get(point.x, point.y))
+    }
+}
+
+/// A rectangular part of a Map, with Points relative to its top left corner
+pub struct MapView<'a, T> {
+    map: &'a Map<T>,
+    origin: Point,
+    columns: usize,
+    rows: usize,
+}
+
+impl<T> MapView<'_, T> {
+    pub fn get_rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn get_columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn within(&self, x: Int, y: Int) -> bool {
+        x >= 0 && x < self.columns as Int && y >= 0 && y < self.rows as Int
+    }
+
+    pub fn get(&self, x: Int, y: Int) -> Option<&T> {
+        if self.within(x, y) {
+            self.map.get(self.origin.x + x, self.origin.y + y)
+        } else {
+            None
+        }
+    }
+
+    pub fn point_get(&self, point: &Point) -> Option<&T> {
+        self.get(point.x, point.y)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        let (x, y) = (self.origin.x as usize, self.origin.y as usize);
+        (y..y + self.rows).map(move |y| &self.map[y][x..x + self.columns])
+    }
+
+    /// Copy the viewed cells into a new Map
+    pub fn to_map(&self) -> Map<T>
+    where
+        T: Clone,
+    {
+        self.rows().map(|row| row.to_vec()).collect()
     }
 }
 
-use std::ops::{Deref, DerefMut};
+/// Display a Map with one line per row, e.g. to compare it to the puzzle description
+impl<T: CharConvertable> Display for Map<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lines: Vec<String> = self
+            .rows()
+            .map(|row| row.map(CharConvertable::to_char).collect())
+            .collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl<T: CharConvertable + Clone> Display for SparseMap<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_map())
+    }
+}
+
+/// Parse a Map with one row per line, panics on a char that `T` cannot be converted from
+impl<T: CharConvertable> From<InputLines> for Map<T> {
+    fn from(input: InputLines) -> Self {
+        let lines: Vec<String> = input.into();
+        lines
+            .iter()
+            .map(|line| line.chars().map(T::from_char).collect())
+            .collect()
+    }
+}
+
+use std::cmp::Ordering;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
 
 use aoc::define_convertable_enum;
 pub use Direction::*;
@@ -104,13 +510,121 @@ define_convertable_enum! {
     }
 }
 
-impl Display for Direction {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+impl Direction {
+    /// All directions, clockwise starting at North
+    pub const ALL: [Direction; 4] = [North, East, South, West];
+
+    fn index(&self) -> usize {
+        Direction::ALL.iter().position(|d| d == self).unwrap()
+    }
+
+    pub fn turn_right(&self) -> Direction {
+        Direction::ALL[(self.index() + 1) % 4]
+    }
+
+    pub fn turn_left(&self) -> Direction {
+        Direction::ALL[(self.index() + 3) % 4]
     }
+
+    pub fn opposite(&self) -> Direction {
+        Direction::ALL[(self.index() + 2) % 4]
+    }
+
+    /// Direction from the letter codes used in puzzle inputs: `U/R/D/L` or `N/E/S/W`, `None` for
+    /// any other char
+    pub fn from_letter(c: char) -> Option<Direction> {
+        match c {
+            'U' | 'N' => Some(North),
+            'R' | 'E' => Some(East),
+            'D' | 'S' => Some(South),
+            'L' | 'W' => Some(West),
+            _ => None,
+        }
+    }
+
+    /// The unit vector of this Direction, where North is towards lower y
+    pub fn to_point(self) -> Point {
+        Direction8::from(self).to_point()
+    }
+}
+
+/// The 4 Directions and the diagonals between them
+#[derive(PartialEq, Debug, Clone, Eq, Hash, Copy, PartialOrd)]
+pub enum Direction8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
 }
 
-#[derive(PartialEq, Clone, Eq, Hash, Copy, PartialOrd)]
+impl Direction8 {
+    /// All directions, clockwise starting at North
+    pub const ALL: [Direction8; 8] = [
+        Direction8::North,
+        Direction8::NorthEast,
+        Direction8::East,
+        Direction8::SouthEast,
+        Direction8::South,
+        Direction8::SouthWest,
+        Direction8::West,
+        Direction8::NorthWest,
+    ];
+
+    pub fn iter() -> impl Iterator<Item = Direction8> {
+        Direction8::ALL.into_iter()
+    }
+
+    /// Rotate clockwise by `eighths` of a full turn, counterclockwise if negative
+    pub fn rotate(&self, eighths: Int) -> Direction8 {
+        let index = Direction8::ALL.iter().position(|d| d == self).unwrap() as Int;
+        Direction8::ALL[(index + eighths).rem_euclid(8) as usize]
+    }
+
+    pub fn turn_right(&self) -> Direction8 {
+        self.rotate(2)
+    }
+
+    pub fn turn_left(&self) -> Direction8 {
+        self.rotate(-2)
+    }
+
+    pub fn opposite(&self) -> Direction8 {
+        self.rotate(4)
+    }
+
+    /// The vector of this Direction8, where North is towards lower y
+    pub fn to_point(self) -> Point {
+        let (x, y) = match self {
+            Direction8::North => (0, -1),
+            Direction8::NorthEast => (1, -1),
+            Direction8::East => (1, 0),
+            Direction8::SouthEast => (1, 1),
+            Direction8::South => (0, 1),
+            Direction8::SouthWest => (-1, 1),
+            Direction8::West => (-1, 0),
+            Direction8::NorthWest => (-1, -1),
+        };
+        Point::new(x, y)
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(direction: Direction) -> Direction8 {
+        match direction {
+            North => Direction8::North,
+            East => Direction8::East,
+            South => Direction8::South,
+            West => Direction8::West,
+        }
+    }
+}
+
+/// A position in a grid, where y grows downwards
+#[derive(PartialEq, Clone, Eq, Hash, Copy, PartialOrd, Ord)]
 pub struct Point {
     pub x: Int,
     pub y: Int,
@@ -134,24 +648,8 @@ impl Point {
     }
 
     pub fn move_distance(&self, direction: &Direction, distance: Int) -> Point {
-        match direction {
-            North => Point {
-                x: self.x,
-                y: self.y - distance,
-            },
-            East => Point {
-                x: self.x + distance,
-                y: self.y,
-            },
-            South => Point {
-                x: self.x,
-                y: self.y + distance,
-            },
-            West => Point {
-                x: self.x - distance,
-                y: self.y,
-            },
-        }
+        let unit = direction.to_point();
+        self.translate(unit.x * distance, unit.y * distance)
     }
 
     pub fn new(x: Int, y: Int) -> Point {
@@ -167,4 +665,351 @@ impl Point {
     pub fn distance_to(&self, other: &Point) -> f64 {
         (((self.x - other.x).pow(2) + (self.y - other.y).pow(2)) as f64).sqrt()
     }
+
+    /// The number of steps between two Points when moving in the 4 Directions
+    pub fn manhattan_distance(&self, other: &Point) -> Int {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    /// The number of steps between two Points when also moving diagonally
+    pub fn chebyshev_distance(&self, other: &Point) -> Int {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+
+    /// Rotate a quarter turn clockwise about the origin, so North becomes East
+    pub fn rotate_clockwise(&self) -> Point {
+        Point::new(-self.y, self.x)
+    }
+
+    /// Rotate a quarter turn counterclockwise about the origin, so North becomes West
+    pub fn rotate_counterclockwise(&self) -> Point {
+        Point::new(self.y, -self.x)
+    }
+
+    /// Compare in reading order: by row, then by column, e.g. for `sort_by(Point::reading_order)`
+    pub fn reading_order(&self, other: &Point) -> Ordering {
+        (self.y, self.x).cmp(&(other.y, other.x))
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        self.translate(other.x, other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        self.translate(-other.x, -other.y)
+    }
+}
+
+impl Mul<Int> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: Int) -> Point {
+        Point::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Point) {
+        *self = *self - other;
+    }
+}
+
+/// A position in 3D space, generic over its coordinate type
+#[derive(PartialEq, Debug, Clone, Eq, Hash, Copy, Default)]
+pub struct Point3<T = Int> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point3<T> {
+    pub fn new(x: T, y: T, z: T) -> Point3<T> {
+        Point3 { x, y, z }
+    }
+}
+
+impl<T: Copy + Add<Output = T> + Sub<Output = T> + PartialOrd> Point3<T> {
+    /// The sum of the absolute differences of the coordinates
+    pub fn manhattan_distance(&self, other: &Point3<T>) -> T {
+        let difference = |a: T, b: T| if a > b { a - b } else { b - a };
+        difference(self.x, other.x) + difference(self.y, other.y) + difference(self.z, other.z)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point3<T> {
+    type Output = Point3<T>;
+
+    fn add(self, other: Point3<T>) -> Point3<T> {
+        Point3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point3<T> {
+    type Output = Point3<T>;
+
+    fn sub(self, other: Point3<T>) -> Point3<T> {
+        Point3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point3<T> {
+    type Output = Point3<T>;
+
+    fn mul(self, factor: T) -> Point3<T> {
+        Point3::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+}
+
+impl<T: Add<Output = T> + Copy> AddAssign for Point3<T> {
+    fn add_assign(&mut self, other: Point3<T>) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Sub<Output = T> + Copy> SubAssign for Point3<T> {
+    fn sub_assign(&mut self, other: Point3<T>) {
+        *self = *self - other;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+    use proptest::prelude::*;
+
+    fn example_map() -> Map<char> {
+        InputLines::from("ab\ncd\nef".to_string()).into()
+    }
+
+    #[test]
+    fn test_map_transformations() {
+        let map = example_map();
+        test!("ab\ncd\nef", map.to_string());
+        test!("ace\nbdf", map.transpose().to_string());
+        test!("eca\nfdb", map.rotate_clockwise().to_string());
+        test!("bdf\nace", map.rotate_counterclockwise().to_string());
+        test!("ba\ndc\nfe", map.flip_horizontal().to_string());
+        test!("ef\ncd\nab", map.flip_vertical().to_string());
+        test!(
+            map.to_string(),
+            map.rotate_clockwise()
+                .rotate_clockwise()
+                .rotate_clockwise()
+                .rotate_clockwise()
+                .to_string()
+        );
+        test!(
+            "AB\nCD\nEF",
+            map.map(|c| c.to_ascii_uppercase()).to_string()
+        );
+    }
+
+    #[test]
+    fn test_map_access() {
+        let mut map = example_map();
+        test!(Some(&'d'), map.get(1, 1));
+        test!(None::<&char>, map.get(2, 1));
+        map.set(&Point::new(1, 1), 'x');
+        *map.get_mut(0, 2).unwrap() = 'y';
+        test!("ab\ncx\nyf", map.to_string());
+        test!(
+            vec!['b', 'x', 'f'],
+            map.column(1).copied().collect::<Vec<_>>()
+        );
+        test!(3, map.columns().next().unwrap().count());
+        test!(
+            vec![(Point::new(1, 2), &'f')],
+            map.enumerate()
+                .filter(|(_, c)| **c == 'f')
+                .collect::<Vec<_>>()
+        );
+        test!(5, map.get_neighbours(&Point::new(1, 1)).len());
+        test!(3, map.get_neighbours(&Point::new(0, 0)).len());
+        test!(0, Map::<char>::from_iter(Vec::new()).get_columns());
+        test!('f', map[Point::new(1, 2)]);
+        test!('f', map[(1, 2)]);
+        test!('f', map[2][1]);
+        map[(1, 2)] = 'z';
+        test!(Some(&'z'), map.get(1, 2));
+        test!(
+            true,
+            map == Map::from(vec![vec!['a', 'b'], vec!['c', 'x'], vec!['y', 'z']])
+        );
+    }
+
+    #[test]
+    fn test_map_view() {
+        let map = example_map();
+        let view = map.view(Point::new(1, 1), 1, 2);
+        test!(Some(&'d'), view.get(0, 0));
+        test!(Some(&'f'), view.get(0, 1));
+        test!(None::<&char>, view.get(1, 0));
+        test!("d\nf", view.to_map().to_string());
+    }
+
+    #[test]
+    fn test_wrapping_and_sparse_maps() {
+        let map = example_map();
+        let wrapping = map.wrapping();
+        test!(Some(&'a'), wrapping.get(2, 3));
+        test!(Some(&'f'), wrapping.get(-1, -1));
+        test!(4, wrapping.get_adjacent(&Point::new(0, 0)).len());
+        test!(2, map.get_adjacent(&Point::new(0, 0)).len());
+
+        let mut sparse = SparseMap::from_map(&map, '.');
+        test!(&'.', sparse.get(-5, 100));
+        sparse.set(&Point::new(-1, 3), 'x');
+        test!(vec![Point::new(-1, 3)], sparse.find(|c| *c == 'x'));
+        test!(Some((Point::new(-1, 0), Point::new(1, 3))), sparse.bounds());
+        test!(".ab\n.cd\n.ef\nx..", sparse.to_string());
+    }
+
+    #[test]
+    fn test_directions() {
+        test!(East, North.turn_right());
+        test!(West, North.turn_left());
+        test!(North, West.turn_right());
+        test!(East, West.opposite());
+        test!(
+            vec![North, East, South, West],
+            Direction::iter().collect::<Vec<_>>()
+        );
+        test!(Some(South), Direction::from_letter('D'));
+        test!(Some(West), Direction::from_letter('W'));
+        test!(None::<Direction>, Direction::from_letter('X'));
+        test!(Point::new(0, -1), North.to_point());
+        test!(Point::new(3, 5), Point::new(1, 5).move_distance(&East, 2));
+
+        test!(Direction8::NorthWest, Direction8::North.rotate(-1));
+        test!(Direction8::SouthEast, Direction8::NorthEast.turn_right());
+        test!(Direction8::SouthWest, Direction8::NorthEast.opposite());
+        test!(Point::new(-1, 1), Direction8::SouthWest.to_point());
+        test!(Direction8::West, Direction8::from(West));
+        test!(8, Direction8::iter().count());
+    }
+
+    define_convertable_enum! {
+        Cell {
+            Empty => '.' | ' ',
+            Wall(Option<Point>) => '#',
+            Start(Int = 1) => 'S'; "start" | "S0",
+        }
+    }
+
+    #[test]
+    fn test_convertable_enum() {
+        test!(Some(Cell::Empty), Cell::try_from_char(' '));
+        test!(None::<Cell>, Cell::try_from_char('x'));
+        test!(Cell::Wall(None), Cell::from_char('#'));
+        test!(Cell::Start(1), "S".parse().unwrap());
+        test!(Cell::Start(1), "start".parse().unwrap());
+        test!(Cell::Start(1), "S0".parse().unwrap());
+        test!(Cell::Empty, " ".parse().unwrap());
+        test!(
+            Err::<Cell, _>("Invalid Cell: 'S#'.".to_string()),
+            "S#".parse()
+        );
+        test!(Cell::Empty, Cell::try_from('.').unwrap());
+        test!('#', Cell::Wall(Some(Point::new(1, 2))).to_char());
+        test!(
+            ".#S",
+            Cell::iter().map(|c| c.to_string()).collect::<String>()
+        );
+        test!('v', South.to_char());
+
+        let grid: Grid<Cell> = InputLines::from("S.\n #".to_string()).into();
+        test!(Cell::Wall(None), grid[1][1]);
+        let map: Map<Cell> = InputLines::from("S.\n #".to_string()).into();
+        test!("S.\n.#", map.to_string());
+        let error = InputLines::from("S.\n.x#".to_string())
+            .try_into_grid::<Cell>()
+            .unwrap_err();
+        test!(
+            (2, 2, "x#".to_string()),
+            (error.line, error.column, error.snippet)
+        );
+    }
+
+    #[test]
+    fn test_point_arithmetic() {
+        let (a, b) = (Point::new(1, 2), Point::new(4, -2));
+        test!(Point::new(5, 0), a + b);
+        test!(Point::new(-3, 4), a - b);
+        test!(Point::new(3, 6), a * 3);
+        test!(Point::new(-1, -2), -a);
+        test!(7, a.manhattan_distance(&b));
+        test!(4, a.chebyshev_distance(&b));
+        test!(East.to_point(), North.to_point().rotate_clockwise());
+        test!(West.to_point(), North.to_point().rotate_counterclockwise());
+        let mut points = vec![Point::new(0, 1), Point::new(1, 0), Point::new(0, 0)];
+        points.sort();
+        test!(
+            vec![Point::new(0, 0), Point::new(0, 1), Point::new(1, 0)],
+            points
+        );
+        points.sort_by(Point::reading_order);
+        test!(
+            vec![Point::new(0, 0), Point::new(1, 0), Point::new(0, 1)],
+            points
+        );
+
+        let mut c = Point3::new(1, 2, 3);
+        c += Point3::new(0, 0, -2) * 2;
+        test!(Point3::new(1, 2, -1), c);
+        test!(4, c.manhattan_distance(&Point3::default()));
+        test!(Point3::new(0.5, 0.0, 1.0), Point3::new(1.0, 0.0, 2.0) * 0.5);
+    }
+
+    /// The lines of maps of up to 6 by 6 chars
+    fn maps() -> impl Strategy<Value = String> {
+        (1..=6usize, 1..=6usize).prop_flat_map(|(columns, rows)| {
+            prop::collection::vec(
+                prop::string::string_regex(&format!("[.#O]{{{}}}", columns)).unwrap(),
+                rows,
+            )
+            .prop_map(|lines| lines.join("\n"))
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn transformations_move_cells(lines in maps()) {
+            let map: Map<char> = InputLines::from(lines.clone()).into();
+            let (columns, rows) = (map.get_columns() as Int, map.get_rows() as Int);
+            let (transposed, clockwise) = (map.transpose(), map.rotate_clockwise());
+            let (horizontal, vertical) = (map.flip_horizontal(), map.flip_vertical());
+            for (x, y) in (0..rows).flat_map(|y| (0..columns).map(move |x| (x, y))) {
+                let cell = map.get(x, y);
+                prop_assert_eq!(cell, transposed.get(y, x));
+                prop_assert_eq!(cell, clockwise.get(rows - 1 - y, x));
+                prop_assert_eq!(cell, horizontal.get(columns - 1 - x, y));
+                prop_assert_eq!(cell, vertical.get(x, rows - 1 - y));
+            }
+            prop_assert!(map == map.rotate_counterclockwise().rotate_clockwise());
+            prop_assert_eq!(lines, map.to_string());
+        }
+    }
 }
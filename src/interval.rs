@@ -0,0 +1,254 @@
+// Sets of integers stored as ranges, for puzzles where the values are far too many to enumerate,
+// such as the seed ranges of 2023 day 5 or the part ratings of 2023 day 19.
+
+use aoc::Int;
+use std::fmt::Debug;
+
+/// The integers from `start` up to, but not including, `end`
+#[derive(PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub struct Interval {
+    pub start: Int,
+    pub end: Int,
+}
+
+impl Debug for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}, {})", self.start, self.end)
+    }
+}
+
+impl Interval {
+    pub fn new(start: Int, end: Int) -> Interval {
+        assert!(start <= end, "Invalid Interval: '[{}, {})'.", start, end);
+        Interval { start, end }
+    }
+
+    /// The integers from `first` up to and including `last`
+    pub fn inclusive(first: Int, last: Int) -> Interval {
+        Interval::new(first, last + 1)
+    }
+
+    pub fn with_length(start: Int, length: Int) -> Interval {
+        Interval::new(start, start + length)
+    }
+
+    pub fn len(&self) -> Int {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn contains(&self, value: Int) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// The values in both Intervals, which may be empty
+    pub fn intersection(&self, other: &Interval) -> Interval {
+        let (start, end) = (self.start.max(other.start), self.end.min(other.end));
+        Interval::new(start, end.max(start))
+    }
+
+    pub fn shift(&self, by: Int) -> Interval {
+        Interval::new(self.start + by, self.end + by)
+    }
+}
+
+/// A set of integers, stored as sorted Intervals that are not empty, and neither overlap nor touch
+#[derive(PartialEq, Eq, Hash, Clone, Default)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl Debug for IntervalSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(&self.intervals).finish()
+    }
+}
+
+impl IntervalSet {
+    pub fn new() -> IntervalSet {
+        IntervalSet::default()
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The number of values in the set
+    pub fn len(&self) -> Int {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    pub fn contains(&self, value: Int) -> bool {
+        self.intervals.iter().any(|i| i.contains(value))
+    }
+
+    pub fn min(&self) -> Option<Int> {
+        self.intervals.first().map(|i| i.start)
+    }
+
+    pub fn max(&self) -> Option<Int> {
+        self.intervals.last().map(|i| i.end - 1)
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        *self = self.union(&interval.into());
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        self.intervals
+            .iter()
+            .chain(other.intervals.iter())
+            .copied()
+            .collect()
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        self.intervals
+            .iter()
+            .flat_map(|a| other.intervals.iter().map(|b| a.intersection(b)))
+            .collect()
+    }
+
+    /// The values in this set, but not in `other`
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut result = Vec::new();
+        for interval in &self.intervals {
+            let mut start = interval.start;
+            for removed in &other.intervals {
+                if removed.end <= start || removed.start >= interval.end {
+                    continue;
+                }
+                if removed.start > start {
+                    result.push(Interval::new(start, removed.start));
+                }
+                start = start.max(removed.end);
+            }
+            if start < interval.end {
+                result.push(Interval::new(start, interval.end));
+            }
+        }
+        result.into_iter().collect()
+    }
+
+    /// Split into the values inside `other` and the values outside of it
+    pub fn split(&self, other: &IntervalSet) -> (IntervalSet, IntervalSet) {
+        (self.intersection(other), self.difference(other))
+    }
+
+    pub fn shift(&self, by: Int) -> IntervalSet {
+        IntervalSet {
+            intervals: self.intervals.iter().map(|i| i.shift(by)).collect(),
+        }
+    }
+}
+
+impl From<Interval> for IntervalSet {
+    fn from(interval: Interval) -> IntervalSet {
+        IntervalSet::from_iter([interval])
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    /// Sort the Intervals, and merge the ones that overlap or touch
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> IntervalSet {
+        let mut intervals: Vec<Interval> = iter.into_iter().filter(|i| !i.is_empty()).collect();
+        intervals.sort();
+        let mut merged: Vec<Interval> = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            match merged.last_mut() {
+                Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+                _ => merged.push(interval),
+            }
+        }
+        IntervalSet { intervals: merged }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+    use proptest::prelude::*;
+    use std::collections::BTreeSet;
+
+    fn set(intervals: &[(Int, Int)]) -> IntervalSet {
+        intervals
+            .iter()
+            .map(|&(a, b)| Interval::new(a, b))
+            .collect()
+    }
+
+    #[test]
+    fn test_interval_set() {
+        let a = set(&[(5, 8), (0, 3), (3, 4), (10, 10)]);
+        test!(
+            vec![Interval::new(0, 4), Interval::new(5, 8)],
+            a.intervals()
+        );
+        test!(7, a.len());
+        test!(Some(0), a.min());
+        test!(Some(7), a.max());
+        let b = set(&[(2, 6)]);
+        test!(set(&[(0, 8)]), a.union(&b));
+        test!(set(&[(2, 4), (5, 6)]), a.intersection(&b));
+        test!(set(&[(0, 2), (6, 8)]), a.difference(&b));
+        test!(set(&[(4, 5)]), b.difference(&a));
+        test!(set(&[(-10, -6), (-5, -2)]), a.shift(-10));
+        test!((a.intersection(&b), a.difference(&b)), a.split(&b));
+        test!(
+            true,
+            Interval::new(0, 3)
+                .intersection(&Interval::new(5, 6))
+                .is_empty()
+        );
+        test!(4, Interval::inclusive(1, 4).len());
+    }
+
+    fn sets() -> impl Strategy<Value = IntervalSet> {
+        prop::collection::vec((-20..20 as Int, 0..10 as Int), 0..5).prop_map(|intervals| {
+            intervals
+                .into_iter()
+                .map(|(start, length)| Interval::with_length(start, length))
+                .collect()
+        })
+    }
+
+    fn values(set: &IntervalSet) -> BTreeSet<Int> {
+        set.intervals()
+            .iter()
+            .flat_map(|i| i.start..i.end)
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn matches_brute_force(a in sets(), b in sets(), by in -10..10 as Int) {
+            let (x, y) = (values(&a), values(&b));
+            prop_assert_eq!(x.len() as Int, a.len());
+            prop_assert_eq!(values(&a.union(&b)), &x | &y);
+            prop_assert_eq!(values(&a.intersection(&b)), &x & &y);
+            prop_assert_eq!(values(&a.difference(&b)), &x - &y);
+            prop_assert_eq!(values(&a.shift(by)), x.iter().map(|v| v + by).collect());
+            prop_assert_eq!(a.min(), x.first().copied());
+            prop_assert_eq!(a.max(), x.last().copied());
+            for value in -30..30 {
+                prop_assert_eq!(x.contains(&value), a.contains(value));
+            }
+        }
+
+        #[test]
+        fn is_normalized(a in sets(), b in sets()) {
+            for set in [a.union(&b), a.intersection(&b), a.difference(&b)] {
+                prop_assert!(set.intervals().iter().all(|i| !i.is_empty()));
+                prop_assert!(set.intervals().windows(2).all(|w| w[0].end < w[1].start));
+            }
+        }
+    }
+}
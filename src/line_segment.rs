@@ -1,82 +1,517 @@
-type Int = i64;
+// Line segments and rays on grid Points, in 2D and 3D. Intersections are computed exactly, with
+// rational coordinates in i128, so inputs such as hailstones far from the origin don't lose
+// precision; use `to_f64` for an approximation.
 
-struct Point {
-    x: Int,
-    y: Int,
+use crate::grid::{Point, Point3};
+use crate::math;
+use aoc::Int;
+use std::cmp::Ordering;
+use std::fmt::Display;
+
+/// An exact fraction, always in lowest terms with a positive denominator
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub struct Rational {
+    pub numerator: i128,
+    pub denominator: i128,
+}
+
+impl Rational {
+    pub fn new(numerator: i128, denominator: i128) -> Rational {
+        assert!(denominator != 0, "Invalid Rational: '{}/0'.", numerator);
+        let divisor = math::gcd(numerator.unsigned_abs(), denominator.unsigned_abs()) as i128
+            * denominator.signum();
+        Rational {
+            numerator: numerator / divisor,
+            denominator: denominator / divisor,
+        }
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator == 1
+    }
+
+    /// The value, if it is an integer that fits in an Int
+    pub fn to_int(self) -> Option<Int> {
+        match self.is_integer() {
+            true => Int::try_from(self.numerator).ok(),
+            false => None,
+        }
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+}
+
+impl From<Int> for Rational {
+    fn from(value: Int) -> Rational {
+        Rational::new(value.into(), 1)
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Rational) -> Ordering {
+        (self.numerator * other.denominator).cmp(&(other.numerator * self.denominator))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Rational) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.is_integer() {
+            true => write!(f, "{}", self.numerator),
+            false => write!(f, "{}/{}", self.numerator, self.denominator),
+        }
+    }
+}
+
+/// A 2D point with exact rational coordinates
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub struct RationalPoint {
+    pub x: Rational,
+    pub y: Rational,
+}
+
+impl RationalPoint {
+    /// `origin + direction * numerator / denominator`
+    fn along(origin: Point, direction: Point, numerator: i128, denominator: i128) -> RationalPoint {
+        let coordinate = |o: Int, d: Int| {
+            Rational::new(o as i128 * denominator + d as i128 * numerator, denominator)
+        };
+        RationalPoint {
+            x: coordinate(origin.x, direction.x),
+            y: coordinate(origin.y, direction.y),
+        }
+    }
+
+    /// The grid Point, if both coordinates are integers
+    pub fn to_point(self) -> Option<Point> {
+        Some(Point::new(self.x.to_int()?, self.y.to_int()?))
+    }
+
+    pub fn to_f64(self) -> (f64, f64) {
+        (self.x.to_f64(), self.y.to_f64())
+    }
+}
+
+impl From<Point> for RationalPoint {
+    fn from(point: Point) -> RationalPoint {
+        RationalPoint {
+            x: point.x.into(),
+            y: point.y.into(),
+        }
+    }
+}
+
+fn cross(a: Point, b: Point) -> i128 {
+    a.x as i128 * b.y as i128 - a.y as i128 * b.x as i128
+}
+
+fn dot(a: Point, b: Point) -> i128 {
+    a.x as i128 * b.x as i128 + a.y as i128 * b.y as i128
+}
+
+/// Where `p + t * r` and `q + u * s` cross, as `(t, u)` with common denominator `d > 0`, returned
+/// as `(t * d, u * d, d)`. `None` if the lines are parallel or collinear.
+fn crossing(p: Point, r: Point, q: Point, s: Point) -> Option<(i128, i128, i128)> {
+    let denominator = cross(r, s);
+    if denominator == 0 {
+        return None;
+    }
+    let (t, u) = (cross(q - p, s), cross(q - p, r));
+    Some(match denominator.signum() {
+        1 => (t, u, denominator),
+        _ => (-t, -u, -denominator),
+    })
 }
 
+/// How two segments meet
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Intersection {
+    None,
+    Point(RationalPoint),
+    /// Collinear segments sharing more than a single point
+    Overlap(LineSegment),
+}
+
+/// A segment between two grid Points, including both ends
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 pub struct LineSegment {
-    head: Point,
-    tail: Point,
+    pub head: Point,
+    pub tail: Point,
 }
 
 impl LineSegment {
     pub fn new(x1: Int, y1: Int, x2: Int, y2: Int) -> LineSegment {
-        LineSegment {
-            head: Point { x: x1, y: y1 },
-            tail: Point { x: x2, y: y2 },
+        LineSegment::from_points(Point::new(x1, y1), Point::new(x2, y2))
+    }
+
+    pub fn from_points(head: Point, tail: Point) -> LineSegment {
+        LineSegment { head, tail }
+    }
+
+    /// The vector from head to tail
+    pub fn direction(&self) -> Point {
+        self.tail - self.head
+    }
+
+    pub fn is_parallel(&self, other: &LineSegment) -> bool {
+        cross(self.direction(), other.direction()) == 0
+    }
+
+    /// True iff both segments lie on the same line
+    pub fn is_collinear(&self, other: &LineSegment) -> bool {
+        let (r, s) = (self.direction(), other.direction());
+        let line = if r == Point::new(0, 0) { s } else { r };
+        if line == Point::new(0, 0) {
+            return self.head == other.head;
         }
+        cross(r, s) == 0
+            && cross(line, other.head - self.head) == 0
+            && cross(line, other.tail - self.head) == 0
     }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        let (r, offset) = (self.direction(), *point - self.head);
+        if r == Point::new(0, 0) {
+            return offset == r;
+        }
+        cross(r, offset) == 0 && (0..=dot(r, r)).contains(&dot(r, offset))
+    }
+
+    pub fn intersects(&self, other: &LineSegment) -> bool {
+        self.intersection(other) != Intersection::None
+    }
+
+    pub fn intersection(&self, other: &LineSegment) -> Intersection {
+        let (p, r, q, s) = (self.head, self.direction(), other.head, other.direction());
+        if let Some((t, u, d)) = crossing(p, r, q, s) {
+            return match (0..=d).contains(&t) && (0..=d).contains(&u) {
+                true => Intersection::Point(RationalPoint::along(p, r, t, d)),
+                false => Intersection::None,
+            };
+        }
+        if !self.is_collinear(other) {
+            return Intersection::None;
+        }
+        // Order the ends of both segments along their common line, and take the overlap
+        let line = if r == Point::new(0, 0) { s } else { r };
+        let key = |point: &Point| dot(line, *point - p);
+        let ends = |a: Point, b: Point| if key(&a) <= key(&b) { (a, b) } else { (b, a) };
+        let ((a_low, a_high), (b_low, b_high)) = (ends(self.head, self.tail), ends(q, q + s));
+        let low = if key(&a_low) >= key(&b_low) {
+            a_low
+        } else {
+            b_low
+        };
+        let high = if key(&a_high) <= key(&b_high) {
+            a_high
+        } else {
+            b_high
+        };
+        match key(&low).cmp(&key(&high)) {
+            Ordering::Greater => Intersection::None,
+            Ordering::Equal => Intersection::Point(low.into()),
+            Ordering::Less => Intersection::Overlap(LineSegment::from_points(low, high)),
+        }
+    }
+
+    /// The intersection as floating point coordinates, or `None` if the segments don't meet in a
+    /// single point
+    pub fn intersection_f64(&self, other: &LineSegment) -> Option<(f64, f64)> {
+        match self.intersection(other) {
+            Intersection::Point(point) => Some(point.to_f64()),
+            _ => None,
+        }
+    }
+}
+
+/// A half-line from `origin`, e.g. the future path of something moving with velocity `direction`
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub struct Ray {
+    pub origin: Point,
+    pub direction: Point,
+}
+
+impl Ray {
+    pub fn new(origin: Point, direction: Point) -> Ray {
+        Ray { origin, direction }
+    }
+
+    /// Where two rays cross. Parallel rays have no single crossing point, so they return `None`.
+    pub fn intersection(&self, other: &Ray) -> Option<RationalPoint> {
+        let (t, u, d) = crossing(self.origin, self.direction, other.origin, other.direction)?;
+        match t >= 0 && u >= 0 {
+            true => Some(RationalPoint::along(self.origin, self.direction, t, d)),
+            false => None,
+        }
+    }
+}
+
+fn cross3(a: Point3, b: Point3) -> Point3<i128> {
+    let (a, b) = (widen(a), widen(b));
+    Point3::new(
+        a.y * b.z - a.z * b.y,
+        a.z * b.x - a.x * b.z,
+        a.x * b.y - a.y * b.x,
+    )
+}
+
+fn dot3(a: Point3<i128>, b: Point3<i128>) -> i128 {
+    a.x * b.x + a.y * b.y + a.z * b.z
+}
+
+fn widen(a: Point3) -> Point3<i128> {
+    Point3::new(a.x.into(), a.y.into(), a.z.into())
+}
+
+/// Like `crossing`, for lines in 3D. `None` if the lines are parallel or skew.
+fn crossing3(p: Point3, r: Point3, q: Point3, s: Point3) -> Option<(i128, i128, i128)> {
+    let normal = cross3(r, s);
+    let denominator = dot3(normal, normal);
+    if denominator == 0 || dot3(widen(q - p), normal) != 0 {
+        return None;
+    }
+    let t = dot3(cross3(q - p, s), normal);
+    let u = dot3(cross3(q - p, r), normal);
+    Some((t, u, denominator))
+}
+
+/// `origin + direction * numerator / denominator`, with exact rational coordinates
+fn along3(
+    origin: Point3,
+    direction: Point3,
+    numerator: i128,
+    denominator: i128,
+) -> Point3<Rational> {
+    let coordinate = |o: Int, d: Int| {
+        Rational::new(o as i128 * denominator + d as i128 * numerator, denominator)
+    };
+    Point3::new(
+        coordinate(origin.x, direction.x),
+        coordinate(origin.y, direction.y),
+        coordinate(origin.z, direction.z),
+    )
 }
 
-fn orientation(p: &Point, q: &Point, r: &Point) -> i32 {
-    let val = (q.y - p.y) * (r.x - q.x) - (q.x - p.x) * (r.y - q.y);
+/// A segment between two Points in 3D, including both ends
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub struct LineSegment3 {
+    pub head: Point3,
+    pub tail: Point3,
+}
+
+impl LineSegment3 {
+    pub fn new(head: Point3, tail: Point3) -> LineSegment3 {
+        LineSegment3 { head, tail }
+    }
+
+    pub fn direction(&self) -> Point3 {
+        self.tail - self.head
+    }
+
+    pub fn is_parallel(&self, other: &LineSegment3) -> bool {
+        cross3(self.direction(), other.direction()) == Point3::default()
+    }
 
-    if val == 0 {
-        return 0; // Collinear
-    } else if val > 0 {
-        return 1; // Clockwise
-    } else {
-        return 2; // Counterclockwise
+    /// The shadow of this segment on the xy plane, looking down the z axis
+    pub fn projected_xy(&self) -> LineSegment {
+        LineSegment::new(self.head.x, self.head.y, self.tail.x, self.tail.y)
+    }
+
+    /// Where two segments cross, `None` if they don't, or if they are parallel
+    pub fn intersection(&self, other: &LineSegment3) -> Option<Point3<Rational>> {
+        let (p, r) = (self.head, self.direction());
+        let (t, u, d) = crossing3(p, r, other.head, other.direction())?;
+        match (0..=d).contains(&t) && (0..=d).contains(&u) {
+            true => Some(along3(p, r, t, d)),
+            false => None,
+        }
     }
 }
 
-fn on_segment(p: &Point, q: &Point, r: &Point) -> bool {
-    if q.x <= p.x.max(r.x) && q.x >= p.x.min(r.x) && q.y <= p.y.max(r.y) && q.y >= p.y.min(r.y) {
-        return true;
+/// A half-line in 3D from `origin`, e.g. the future path of a hailstone
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub struct Ray3 {
+    pub origin: Point3,
+    pub direction: Point3,
+}
+
+impl Ray3 {
+    pub fn new(origin: Point3, direction: Point3) -> Ray3 {
+        Ray3 { origin, direction }
+    }
+
+    /// The shadow of this ray on the xy plane, looking down the z axis
+    pub fn projected_xy(&self) -> Ray {
+        Ray::new(
+            Point::new(self.origin.x, self.origin.y),
+            Point::new(self.direction.x, self.direction.y),
+        )
+    }
+
+    /// Where two rays cross, `None` if they don't, or if they are parallel
+    pub fn intersection(&self, other: &Ray3) -> Option<Point3<Rational>> {
+        let (t, u, d) = crossing3(self.origin, self.direction, other.origin, other.direction)?;
+        match t >= 0 && u >= 0 {
+            true => Some(along3(self.origin, self.direction, t, d)),
+            false => None,
+        }
     }
-    false
 }
 
-/// Returns true iff a and b intersect
-pub fn intersects(a: &LineSegment, b: &LineSegment) -> bool {
-    let p1 = &a.head;
-    let q1 = &a.tail;
-    let p2 = &b.head;
-    let q2 = &b.tail;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+    use proptest::prelude::*;
 
-    // Find the four orientations needed for the general and special cases
-    let o1 = orientation(p1, q1, p2);
-    let o2 = orientation(p1, q1, q2);
-    let o3 = orientation(p2, q2, p1);
-    let o4 = orientation(p2, q2, q1);
+    fn point(x: Int, y: Int) -> RationalPoint {
+        Point::new(x, y).into()
+    }
 
-    // General case
-    if o1 != o2 && o3 != o4 {
-        return true;
+    fn point3(point: Point3) -> Point3<Rational> {
+        Point3::new(point.x.into(), point.y.into(), point.z.into())
     }
 
-    // Special cases
-    // p1, q1 and p2 are collinear and p2 lies on segment p1q1
-    if o1 == 0 && on_segment(p1, p2, q1) {
-        return true;
+    #[test]
+    fn test_intersection() {
+        let a = LineSegment::new(0, 0, 4, 4);
+        test!(
+            Intersection::Point(point(2, 2)),
+            a.intersection(&LineSegment::new(0, 4, 4, 0))
+        );
+        test!(
+            Intersection::Point(RationalPoint {
+                x: Rational::new(1, 2),
+                y: Rational::new(1, 2)
+            }),
+            a.intersection(&LineSegment::new(0, 1, 1, 0))
+        );
+        test!(
+            Some((0.5, 0.5)),
+            a.intersection_f64(&LineSegment::new(0, 1, 1, 0))
+        );
+        test!(
+            Intersection::None,
+            a.intersection(&LineSegment::new(0, 1, 4, 5))
+        );
+        test!(true, a.is_parallel(&LineSegment::new(0, 1, 4, 5)));
+        test!(false, a.is_collinear(&LineSegment::new(0, 1, 4, 5)));
+        test!(
+            Intersection::Overlap(LineSegment::new(2, 2, 4, 4)),
+            a.intersection(&LineSegment::new(6, 6, 2, 2))
+        );
+        test!(
+            Intersection::Point(point(4, 4)),
+            a.intersection(&LineSegment::new(4, 4, 6, 6))
+        );
+        test!(
+            Intersection::None,
+            a.intersection(&LineSegment::new(5, 5, 6, 6))
+        );
+        test!(
+            Intersection::Point(point(1, 1)),
+            a.intersection(&LineSegment::new(1, 1, 1, 1))
+        );
+        test!("-3/4", Rational::new(6, -8).to_string());
     }
 
-    // p1, q1 and q2 are collinear and q2 lies on segment p1q1
-    if o2 == 0 && on_segment(p1, q2, q1) {
-        return true;
+    #[test]
+    fn test_rays() {
+        // The first two hailstones of the example of 2023 day 24
+        let a = Ray3::new(Point3::new(19, 13, 30), Point3::new(-2, 1, -2));
+        let b = Ray3::new(Point3::new(18, 19, 22), Point3::new(-1, -1, -2));
+        let crossing = a.projected_xy().intersection(&b.projected_xy()).unwrap();
+        test!(
+            (14.333, 15.333),
+            (
+                (crossing.x.to_f64() * 1000.0).trunc() / 1000.0,
+                (crossing.y.to_f64() * 1000.0).trunc() / 1000.0
+            )
+        );
+        test!(None::<Point3<Rational>>, a.intersection(&b));
+        // Skew: x and y meet at 18, 15, but z is 14 for one and 19 1/3 for the other
+        let c = Ray3::new(Point3::new(24, 13, 10), Point3::new(-3, 1, 2));
+        let d = Ray3::new(Point3::new(18, 19, 22), Point3::new(0, -3, -2));
+        test!(None::<Point3<Rational>>, c.intersection(&d));
+        test!(
+            Some(point3(Point3::new(18, 15, 14))),
+            c.intersection(&Ray3::new(Point3::new(18, 10, 9), Point3::new(0, 1, 1)))
+        );
+        test!(
+            None::<RationalPoint>,
+            Ray::new(Point::new(0, 0), Point::new(1, 0))
+                .intersection(&Ray::new(Point::new(-1, 1), Point::new(-1, -1)))
+        );
     }
 
-    // p2, q2 and p1 are collinear and p1 lies on segment p2q2
-    if o3 == 0 && on_segment(p2, p1, q2) {
-        return true;
+    fn segments() -> impl Strategy<Value = LineSegment> {
+        (-6..=6 as Int, -6..=6 as Int, -6..=6 as Int, -6..=6 as Int)
+            .prop_map(|(x1, y1, x2, y2)| LineSegment::new(x1, y1, x2, y2))
     }
 
-    // p2, q2 and q1 are collinear and q1 lies on segment p2q2
-    if o4 == 0 && on_segment(p2, q1, q2) {
-        return true;
+    fn points3() -> impl Strategy<Value = Point3> {
+        (-50..=50 as Int, -50..=50 as Int, -50..=50 as Int)
+            .prop_map(|(x, y, z)| Point3::new(x, y, z))
     }
 
-    // Doesn't fall in any of the above cases
-    false
+    proptest! {
+        #[test]
+        fn intersection_is_symmetric(a in segments(), b in segments()) {
+            let normalize = |intersection| match intersection {
+                Intersection::Overlap(s) if s.tail < s.head => {
+                    Intersection::Overlap(LineSegment::from_points(s.tail, s.head))
+                }
+                other => other,
+            };
+            prop_assert_eq!(normalize(a.intersection(&b)), normalize(b.intersection(&a)));
+        }
+
+        #[test]
+        fn intersection_matches_lattice_points(a in segments(), b in segments()) {
+            let shared: Vec<Point> = (-6..=6)
+                .flat_map(|y| (-6..=6).map(move |x| Point::new(x, y)))
+                .filter(|p| a.contains(p) && b.contains(p))
+                .collect();
+            match a.intersection(&b) {
+                Intersection::None => prop_assert!(shared.is_empty()),
+                Intersection::Point(p) => {
+                    prop_assert!(shared.len() <= 1);
+                    prop_assert_eq!(shared.first().copied(), p.to_point());
+                }
+                Intersection::Overlap(s) => {
+                    prop_assert!(a.is_collinear(&b));
+                    prop_assert!(shared.iter().all(|p| s.contains(p)));
+                    prop_assert!(shared.contains(&s.head) && shared.contains(&s.tail));
+                }
+            }
+        }
+
+        #[test]
+        fn rays_through_a_point_meet_there(
+            target in points3(),
+            r in points3(),
+            s in points3(),
+            t in 0..10 as Int,
+            u in 0..10 as Int,
+        ) {
+            let a = Ray3::new(target - r * t, r);
+            let b = Ray3::new(target - s * u, s);
+            let expected = point3(target);
+            if cross3(r, s) != Point3::default() {
+                prop_assert_eq!(Some(expected), a.intersection(&b));
+                let (c, d) = (
+                    LineSegment3::new(a.origin, target + r),
+                    LineSegment3::new(b.origin, target + s),
+                );
+                prop_assert_eq!(Some(expected), c.intersection(&d));
+            }
+        }
+    }
 }
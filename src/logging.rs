@@ -0,0 +1,152 @@
+// Logger for the `log` crate. Levels are set with `--verbose` and per module with the `AOC_LOG`
+// environment variable, e.g. `AOC_LOG=debug,d20=trace,aoc=off`. Records either go to stderr, or
+// are captured, so they can be attached to the `TestResult` of a day.
+
+use log::{LevelFilter, Log, Metadata, Record};
+use std::cell::RefCell;
+use std::env;
+
+/// Environment variable with comma separated `<level>` or `<module>=<level>` directives
+pub static LOG_ENV: &str = "AOC_LOG";
+
+thread_local! {
+    /// The records captured by `capture` on this thread, `None` if not capturing
+    static CAPTURED: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+struct Logger {
+    default: LevelFilter,
+    /// Levels for modules, overriding `default`
+    modules: Vec<(String, LevelFilter)>,
+}
+
+impl Logger {
+    /// Create a Logger from the number of `--verbose` flags and the directives in `LOG_ENV`
+    fn parse(verbosity: u8, directives: &str) -> Logger {
+        let mut logger = Logger {
+            default: match verbosity {
+                0 => LevelFilter::Warn,
+                1 => LevelFilter::Info,
+                2 => LevelFilter::Debug,
+                _ => LevelFilter::Trace,
+            },
+            modules: Vec::new(),
+        };
+        let parse_level = |level: &str| -> LevelFilter {
+            level
+                .trim()
+                .parse()
+                .unwrap_or_else(|_| panic!("Invalid log level: '{}'.", level))
+        };
+        for directive in directives.split(',').filter(|d| !d.trim().is_empty()) {
+            match directive.split_once('=') {
+                Some((module, level)) => logger
+                    .modules
+                    .push((module.trim().to_string(), parse_level(level))),
+                None => logger.default = parse_level(directive),
+            }
+        }
+        logger
+    }
+
+    /// If `module` names `target` or one of its parents, e.g. `d20` or `y2023::d20` for
+    /// `advent_of_code_2023::y2023::d20`, return where it ends in `target`
+    fn match_end(target: &str, module: &str) -> Option<usize> {
+        if target == module || target.ends_with(&format!("::{}", module)) {
+            Some(target.len())
+        } else if let Some(start) = target.rfind(&format!("::{}::", module)) {
+            Some(start + 2 + module.len())
+        } else if target.starts_with(&format!("{}::", module)) {
+            Some(module.len())
+        } else {
+            None
+        }
+    }
+
+    /// The level of the most specific module matching `target`, or the default level
+    fn level_for(&self, target: &str) -> LevelFilter {
+        self.modules
+            .iter()
+            .filter_map(|(module, level)| Some((Logger::match_end(target, module)?, level)))
+            .max_by_key(|(end, _)| *end)
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default)
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        self.modules
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default, Ord::max)
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level_for(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = format!(
+            "[{} {}:{}] {}",
+            record.level(),
+            record.file().unwrap_or(record.target()),
+            record.line().unwrap_or(0),
+            record.args()
+        );
+        CAPTURED.with(|captured| match captured.borrow_mut().as_mut() {
+            Some(lines) => lines.push(line),
+            None => eprintln!("{}", line),
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+/// Install the Logger, with the level set by the number of `--verbose` flags, and `LOG_ENV`
+pub fn init(verbosity: u8) {
+    let logger = Logger::parse(verbosity, &env::var(LOG_ENV).unwrap_or_default());
+    log::set_max_level(logger.max_level());
+    log::set_boxed_logger(Box::new(logger)).expect("Logger is already installed.");
+}
+
+/// Run `f`, and return its result with the records it logged, instead of printing them
+pub fn capture<T>(f: impl FnOnce() -> T) -> (T, Vec<String>) {
+    let previous = CAPTURED.with(|captured| captured.replace(Some(Vec::new())));
+    let result = f();
+    let lines = CAPTURED.with(|captured| captured.replace(previous));
+    (result, lines.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+
+    #[test]
+    fn test_levels() {
+        let logger = Logger::parse(1, "d20=trace, y2023=debug,aoc=off");
+        test!(
+            LevelFilter::Info,
+            logger.level_for("advent_of_code_2023::grid")
+        );
+        test!(
+            LevelFilter::Trace,
+            logger.level_for("advent_of_code_2023::y2023::d20")
+        );
+        test!(
+            LevelFilter::Debug,
+            logger.level_for("advent_of_code_2023::y2023::d21")
+        );
+        test!(LevelFilter::Off, logger.level_for("aoc"));
+        test!(
+            LevelFilter::Debug,
+            logger.level_for("advent_of_code_2023::y2023::d200")
+        );
+        test!(LevelFilter::Trace, logger.max_level());
+        test!(LevelFilter::Error, Logger::parse(3, "error").default);
+    }
+}
@@ -50,67 +50,122 @@ macro_rules! test {
 
 }
 
-#[macro_export]
-macro_rules! debug {
-    // Match arm with format string parameter
-    ($should_print:expr, $fmt:expr, $($arg:tt)*) => {
-        if $should_print {
-            let message = format!($fmt, $($arg)*);
-            debug!(true, message);
-        }
-    };
-
-    // Match arm with a condition and a single message
-    ($should_print:ident, $message:expr) => {
-        if $should_print {
-            println!("[{}:{}] {:#?}", file!(), line!(), $message);
-        }
-    };
-
-    (true, $message:expr) => {
-        println!("[{}:{}] {}", file!(), line!(), $message);
-    };
-
-    (false, $fmt:expr, $($arg:tt)*) => {
-        // Do nothing
-    };
-
-    (false, $message:expr) => {
-        // Do nothing
-    };
-
-}
-
 // See: https://chatgpt.com/share/d866e424-9d25-441f-a232-bf78c8372d7c
+/// Define an enum that converts from and to chars, e.g. the tiles of a grid in a puzzle input.
+/// A variant may accept several chars, the first one is the one it converts to. After a `;` it may
+/// also accept string tokens, which only `FromStr` parses. A variant may hold a value, which is
+/// `Default::default()` when converted from a char or token, unless one is given:
+///
+/// ```ignore
+/// define_convertable_enum! {
+///     Tile {
+///         Ground => '.' | 'O' | 'I',
+///         Rock(Option<Point>) => '#'; "rock" | "boulder",
+///         Start(Int = 1) => 'S',
+///     }
+/// }
+/// ```
 #[macro_export]
 macro_rules! define_convertable_enum {
-    ($name:ident { $($variant:ident $(($opt:ty))? => $char:expr),* $(,)? }) => {
+    ($name:ident { $($variant:ident $(($opt:ty $(= $value:expr)?))? => $($char:literal)|+ $(; $($token:literal)|+)?),* $(,)? }) => {
         #[derive(PartialEq, Debug, Clone, Eq, Hash, Copy, PartialOrd)]
         pub enum $name {
             $($variant$(($opt))?),*
         }
 
+        #[allow(dead_code)]
         impl $name {
-            pub fn from_char(c: char) -> $name {
+            /// Convert from a char, or `None` if `c` is invalid
+            pub fn try_from_char(c: char) -> Option<$name> {
                 match c {
                     $(
-                        $char => $name::$variant$(($opt::default()))?,
+                        $($char)|+ => Some($name::$variant$(($crate::convertable_value!($opt $(, $value)?)))?),
                     )*
-                    _ => panic!("Invalid {} char: '{}'.", stringify!($name), c),
+                    _ => None,
                 }
             }
 
+            /// Convert from a char, panics if `c` is invalid
+            pub fn from_char(c: char) -> $name {
+                $name::try_from_char(c)
+                    .unwrap_or_else(|| panic!("Invalid {} char: '{}'.", stringify!($name), c))
+            }
+
             pub fn to_char(&self) -> char {
                 match self {
                     $(
-                        $name::$variant$(($opt::default()))? => $char,
+                        $name::$variant$(($crate::convertable_value!(_ $opt)))? => [$($char),+][0],
                     )*
                 }
             }
+
+            /// All variants, in the order they are defined
+            pub fn iter() -> impl Iterator<Item = $name> {
+                vec![$($name::$variant$(($crate::convertable_value!($opt $(, $value)?)))?),*].into_iter()
+            }
+        }
+
+        impl $crate::CharConvertable for $name {
+            fn try_from_char(c: char) -> Option<$name> {
+                $name::try_from_char(c)
+            }
+
+            fn to_char(&self) -> char {
+                $name::to_char(self)
+            }
+        }
+
+        impl TryFrom<char> for $name {
+            type Error = String;
+
+            fn try_from(c: char) -> Result<$name, String> {
+                $name::try_from_char(c)
+                    .ok_or_else(|| format!("Invalid {} char: '{}'.", stringify!($name), c))
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = String;
+
+            /// Convert from a string token of a variant, or a string of a single char
+            fn from_str(s: &str) -> Result<$name, String> {
+                $(
+                    if [$($($token),+)?].contains(&s) {
+                        return Ok($name::$variant$(($crate::convertable_value!($opt $(, $value)?)))?);
+                    }
+                )*
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => $name::try_from(c),
+                    _ => Err(format!("Invalid {}: '{}'.", stringify!($name), s)),
+                }
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self.to_char())
+            }
         }
     };
 }
 
+/// The value of a variant of `define_convertable_enum!` converted from a char or token: the given
+/// one, or the default of its type. `_` followed by a type is a pattern that matches any value.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! convertable_value {
+    (_ $opt:ty) => {
+        _
+    };
+    ($opt:ty) => {
+        <$opt>::default()
+    };
+    ($opt:ty, $value:expr) => {
+        $value
+    };
+}
+
 /// A Vec<String> from a Vec<&str>
 #[macro_export]
 macro_rules! vec_of_strings {
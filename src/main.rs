@@ -1,12 +1,15 @@
-mod grid;
-mod line_segment;
+mod extract;
+mod logging;
 mod macros;
-mod solution;
-mod y2023;
+mod profile;
+mod scaffold;
 
+use aoc::{solution, solutions, SolutionBox};
+use profile::{MatrixRow, Profile};
 use solution::*;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "advent-of-code-2023")]
@@ -15,39 +18,106 @@ struct Cli {
     /// Day to run, one of {1, ..., 25}
     #[arg(short, long)]
     day: Option<Day>,
+
+    /// Inputs to run against: `default` (the aocf cache), a profile in `inputs/`, or `all`
+    #[arg(short, long, default_value = profile::DEFAULT_PROFILE)]
+    profile: String,
+
+    /// Log more, repeat for more detail: -v info, -vv debug, -vvv trace. See also `AOC_LOG`
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
+
+    /// Capture the logs of each day, and only report them for days that fail
+    #[arg(long)]
+    capture_logs: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
-type SolutionBox = Box<dyn Solution>;
+#[derive(Subcommand)]
+enum Command {
+    /// Generate a new day module from a template, and wire it up
+    New {
+        #[arg(short, long)]
+        year: Year,
+
+        /// Day to generate, one of {1, ..., 25}
+        #[arg(short, long)]
+        day: Day,
+
+        /// Overwrite the day module if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Print the examples of a puzzle as `define_examples!` entries, from the aocf cache or a saved page
+    Examples {
+        #[arg(short, long)]
+        year: Year,
+
+        /// Day of the puzzle, one of {1, ..., 25}
+        #[arg(short, long)]
+        day: Day,
+
+        /// Read the puzzle description from a puzzle page saved as HTML, instead of the aocf cache
+        #[arg(long)]
+        html: Option<PathBuf>,
+
+        /// Write the examples to fixture files in `examples/`, instead of printing them inline
+        #[arg(long)]
+        write: bool,
+    },
+    /// Print a random valid input for a day, for stress and differential testing
+    Generate {
+        /// Day to generate an input for, one of {10, 11, 12, 16, 18, 19, 20, 22}
+        #[arg(short, long)]
+        day: Day,
+
+        /// How big the input is, such as the width of a grid or the number of lines
+        #[arg(long, default_value_t = 10)]
+        size: usize,
+
+        /// Seed of the random number generator, the same seed always gives the same input
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+}
 
 fn main() {
     let instant = Instant::now();
     let cli = Cli::parse();
+    logging::init(cli.verbose);
 
-    let aoc_solutions: Vec<SolutionBox> = vec![
-        y2023::d01::Problem::create_box(),
-        y2023::d02::Problem::create_box(),
-        y2023::d03::Problem::create_box(),
-        y2023::d04::Problem::create_box(),
-        y2023::d05::Problem::create_box(),
-        y2023::d06::Problem::create_box(),
-        y2023::d07::Problem::create_box(),
-        y2023::d08::Problem::create_box(),
-        y2023::d09::Problem::create_box(),
-        y2023::d10::Problem::create_box(),
-        y2023::d11::Problem::create_box(),
-        y2023::d12::Problem::create_box(),
-        y2023::d13::Problem::create_box(),
-        y2023::d14::Problem::create_box(),
-        y2023::d15::Problem::create_box(),
-        y2023::d16::Problem::create_box(),
-        y2023::d18::Problem::create_box(),
-        y2023::d19::Problem::create_box(),
-        y2023::d20::Problem::create_box(),
-        y2023::d21::Problem::create_box(),
-        y2023::d22::Problem::create_box(),
-    ];
-
-    let mut test_results: Vec<TestResult> = Vec::new();
+    match cli.command {
+        Some(Command::New { year, day, force }) => {
+            scaffold::new_day(year, day, force);
+            return;
+        }
+        Some(Command::Examples {
+            year,
+            day,
+            html,
+            write,
+        }) => {
+            extract::print_examples(year, day, html.as_deref(), write);
+            return;
+        }
+        Some(Command::Generate { day, size, seed }) => {
+            let input = solutions()
+                .iter()
+                .find(|solution| solution.day() == day)
+                .and_then(|solution| solution.generate_input(&mut Rng::new(seed), size))
+                .unwrap_or_else(|| panic!("No input generator for day: '{}'.", day));
+            print!("{}", input);
+            return;
+        }
+        None => (),
+    }
+
+    let aoc_solutions = solutions();
+
+    let profiles = Profile::select(&cli.profile);
+    let mut test_results: Vec<MatrixRow> = Vec::new();
 
     for (i, aoc_solution) in aoc_solutions.iter().enumerate() {
         if let Some(day) = cli.day {
@@ -56,28 +126,52 @@ fn main() {
             }
         }
 
-        println!(
-            "[{}/{}] Running AoC: {}-{:02}",
-            i,
-            aoc_solutions.len(),
-            aoc_solution.year(),
-            aoc_solution.day()
-        );
-        test_results.push(aoc_solution.run());
-        println!();
+        let mut row = Vec::new();
+        for profile in &profiles {
+            println!(
+                "[{}/{}] Running AoC: {}-{:02} ({})",
+                i,
+                aoc_solutions.len(),
+                aoc_solution.year(),
+                aoc_solution.day(),
+                profile.name
+            );
+            if cli.capture_logs {
+                let (mut result, logs) = logging::capture(|| profile.run(aoc_solution));
+                if let Some(result) = &mut result {
+                    result.logs = logs;
+                }
+                row.push(result);
+            } else {
+                row.push(profile.run(aoc_solution));
+            }
+            println!();
+        }
+        test_results.push(((aoc_solution.year(), aoc_solution.day()), row));
     }
 
-    dbg!(&test_results);
+    let ran: Vec<&TestResult> = test_results
+        .iter()
+        .flat_map(|(_, row)| row.iter().flatten())
+        .collect();
+    profile::print_matrix(&profiles, &test_results);
+    for result in ran.iter().filter(|result| result.is_failure()) {
+        if !result.logs.is_empty() {
+            println!("Logs of failed AoC: {}-{:02}:", result.year, result.day);
+            result.logs.iter().for_each(|line| println!("{}", line));
+        }
+    }
     println!(
         "Ran {} AoC solutions in {:.2?}.",
-        test_results.len(),
+        ran.len(),
         instant.elapsed()
     );
 
-    assert!(!test_results.is_empty());
-    let all_test_results_succeed = test_results.iter().all(|result| match result.p1 {
-        TestStatus::Success(_, _) => true,
-        _ => false,
-    });
+    if ran.is_empty() {
+        let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+        eprintln!("No inputs to run for profile: '{}'.", names.join("', '"));
+        std::process::exit(1);
+    }
+    let all_test_results_succeed = ran.iter().all(|result| !result.is_failure());
     assert!(all_test_results_succeed);
 }
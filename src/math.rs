@@ -0,0 +1,155 @@
+// Number theory for puzzles whose answers are too big to simulate: least common multiples of
+// cycle lengths, congruences and modular arithmetic. Results that may not fit are checked or
+// computed in u128/i128.
+
+use std::ops::Rem;
+
+/// Return the greatest common divisor of a and b
+pub fn gcd<T: Copy + PartialEq + Default + Rem<Output = T>>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a, b);
+    while b != T::default() {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Return the least common multiple of a and b, or `None` if it does not fit in a u64
+pub fn checked_lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Return the least common multiple of all values, or `None` if it does not fit in a u64
+pub fn lcm_of(values: impl IntoIterator<Item = u64>) -> Option<u64> {
+    values.into_iter().try_fold(1, checked_lcm)
+}
+
+/// Return the least common multiple of all values, with room for the product of a few u64s
+pub fn lcm_of_u128(values: impl IntoIterator<Item = u128>) -> u128 {
+    values.into_iter().fold(1, |lcm, value| match value {
+        0 => 0,
+        _ => lcm / gcd(lcm, value) * value,
+    })
+}
+
+/// Return `(g, x, y)` such that `a * x + b * y = g`, where g is the greatest common divisor
+/// See: https://en.wikipedia.org/wiki/Extended_Euclidean_algorithm
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a.abs(), a.signum(), 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a.rem_euclid(b));
+        (g, y, x - a.div_euclid(b) * y)
+    }
+}
+
+/// Return x such that `a * x ≡ 1 (mod modulus)`, if a and modulus are coprime
+pub fn mod_inverse(a: i128, modulus: i128) -> Option<i128> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    match g {
+        1 => Some(x.rem_euclid(modulus)),
+        _ => None,
+    }
+}
+
+/// Return `base ^ exponent (mod modulus)`, by repeated squaring
+pub fn mod_pow(base: u64, exponent: u64, modulus: u64) -> u64 {
+    let modulus = modulus as u128;
+    let (mut result, mut base, mut exponent) = (1 % modulus, base as u128 % modulus, exponent);
+    while exponent > 0 {
+        if exponent % 2 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent /= 2;
+    }
+    result as u64
+}
+
+/// Solve the system `x ≡ residue (mod modulus)` for each congruence, where the moduli don't have
+/// to be coprime. Return `(x, lcm)` with the smallest non-negative x, which repeats every lcm of
+/// the moduli, or `None` if the congruences contradict each other, a modulus is not positive, or
+/// the result overflows.
+/// See: https://en.wikipedia.org/wiki/Chinese_remainder_theorem
+pub fn chinese_remainder(congruences: &[(i128, i128)]) -> Option<(i128, i128)> {
+    congruences
+        .iter()
+        .try_fold((0, 1), |(x, lcm), &(residue, modulus)| {
+            if modulus <= 0 {
+                return None;
+            }
+            // Find t with x + lcm * t ≡ residue (mod modulus)
+            let (g, inverse, _) = extended_gcd(lcm, modulus);
+            let difference = residue.checked_sub(x)?;
+            if difference % g != 0 {
+                return None;
+            }
+            let step = modulus / g;
+            let t = ((difference / g % step).checked_mul(inverse)? % step).rem_euclid(step);
+            let next_lcm = lcm.checked_mul(step)?;
+            Some((
+                x.checked_add(lcm.checked_mul(t)?)?.rem_euclid(next_lcm),
+                next_lcm,
+            ))
+        })
+}
+
+/// Return the largest integer whose square is at most n
+pub fn isqrt(n: u64) -> u64 {
+    let n = n as u128;
+    let mut root = (n as f64).sqrt() as u128;
+    while root * root > n {
+        root -= 1;
+    }
+    while (root + 1) * (root + 1) <= n {
+        root += 1;
+    }
+    root as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+
+    #[test]
+    fn test_math() {
+        test!(6, gcd(54u64, 24));
+        test!(5, gcd(0u128, 5));
+        test!(Some(36), checked_lcm(12, 18));
+        test!(Some(60), lcm_of([3, 4, 5, 6]));
+        test!(None::<u64>, lcm_of([u64::MAX, u64::MAX - 1]));
+        test!(
+            u64::MAX as u128 * (u64::MAX - 1) as u128,
+            lcm_of_u128([u64::MAX as u128, (u64::MAX - 1) as u128])
+        );
+
+        let (g, x, y) = extended_gcd(240, 46);
+        test!(2, g);
+        test!(2, 240 * x + 46 * y);
+        test!(Some(4), mod_inverse(3, 11));
+        test!(None::<i128>, mod_inverse(4, 8));
+        test!(445, mod_pow(4, 13, 497));
+        test!(0, mod_pow(7, 0, 1));
+
+        test!(
+            Some((23, 105)),
+            chinese_remainder(&[(2, 3), (3, 5), (2, 7)])
+        );
+        test!(Some((10, 12)), chinese_remainder(&[(4, 6), (2, 4)]));
+        test!(None::<(i128, i128)>, chinese_remainder(&[(1, 6), (2, 4)]));
+        test!(Some((0, 1)), chinese_remainder(&[]));
+        test!(None::<(i128, i128)>, chinese_remainder(&[(1, 3), (0, 0)]));
+        let big = i128::MAX / 2;
+        test!(
+            None::<(i128, i128)>,
+            chinese_remainder(&[(1, big), (0, big - 2)])
+        );
+
+        test!(4, isqrt(24));
+        test!(5, isqrt(25));
+        test!(u32::MAX as u64, isqrt(u64::MAX));
+    }
+}
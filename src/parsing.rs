@@ -0,0 +1,302 @@
+// Nom parsers for the shapes that keep coming back in puzzle inputs: signed numbers, number lists,
+// `key=value` fields, separated tuples, lines, blank-line-separated blocks, grids and labelled
+// sections. `parse_all` runs a parser on a whole input, and turns a failure into a ParseError
+// that points at the offending line and column.
+
+use crate::solution::Day;
+use aoc::{Grid, Int};
+use nom::bytes::complete::{tag, take_till1};
+use nom::character::complete::{
+    alphanumeric1, char, digit1, line_ending, multispace0, space0, space1,
+};
+use nom::combinator::{map, map_res, opt, recognize};
+use nom::multi::separated_list1;
+use nom::sequence::{pair, preceded, separated_pair, terminated, tuple};
+use nom::IResult;
+use std::fmt::Display;
+
+/// A failure to parse an input, pointing at where it went wrong
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ParseError {
+    /// The day whose input it is, once known
+    pub day: Option<Day>,
+    /// The line of the failure, counting from 1, or 0 if it is not at a line
+    pub line: usize,
+    /// The column of the failure, in characters, counting from 1
+    pub column: usize,
+    /// The rest of the line, from the column of the failure
+    pub snippet: String,
+    /// What the parser expected to find there
+    pub expected: String,
+}
+
+impl ParseError {
+    /// An error at the start of `remaining`, which must be the end of `input`
+    pub fn at(input: &str, remaining: &str, expected: impl Into<String>) -> ParseError {
+        let parsed = &input[..input.len() - remaining.len()];
+        let line_start = parsed.rfind('\n').map_or(0, |i| i + 1);
+        ParseError {
+            day: None,
+            line: parsed.matches('\n').count() + 1,
+            column: parsed[line_start..].chars().count() + 1,
+            snippet: remaining.lines().next().unwrap_or_default().to_string(),
+            expected: expected.into(),
+        }
+    }
+
+    /// An error about the input as a whole, such as a missing section, which is not at any line
+    pub fn of_whole_input(expected: impl Into<String>) -> ParseError {
+        ParseError {
+            day: None,
+            line: 0,
+            column: 0,
+            snippet: String::new(),
+            expected: expected.into(),
+        }
+    }
+
+    pub fn in_day(self, day: Day) -> ParseError {
+        ParseError {
+            day: Some(day),
+            ..self
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "Invalid input of day {}, ", day)?;
+        } else {
+            write!(f, "Invalid input ")?;
+        }
+        if self.line == 0 {
+            return write!(f, "as a whole: expected {}.", self.expected);
+        }
+        write!(
+            f,
+            "at line {}, column {}: expected {}, found '{}'.",
+            self.line, self.column, self.expected, self.snippet
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Run `parser` on all of `input`, which may only have whitespace left over
+pub fn parse_all<'a, O>(
+    input: &'a str,
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> Result<O, ParseError> {
+    match parser(input) {
+        Ok((rest, output)) if rest.trim().is_empty() => Ok(output),
+        Ok((rest, _)) => Err(ParseError::at(input, rest.trim_start(), "end of input")),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => {
+            Err(ParseError::at(input, e.input, e.code.description()))
+        }
+        Err(nom::Err::Incomplete(_)) => Err(ParseError::at(input, "", "more input")),
+    }
+}
+
+/// A value that can be parsed from part of a line, which `#[derive(AocParse)]` builds on
+pub trait Record: Sized {
+    fn parse_record(input: &str) -> IResult<&str, Self>;
+}
+
+impl Record for Int {
+    fn parse_record(input: &str) -> IResult<&str, Int> {
+        number(input)
+    }
+}
+
+impl Record for String {
+    /// Parse a word of letters and digits
+    fn parse_record(input: &str) -> IResult<&str, String> {
+        map(alphanumeric1, String::from)(input)
+    }
+}
+
+/// Parse a number, which may be negative
+pub fn number(input: &str) -> IResult<&str, Int> {
+    map_res(recognize(pair(opt(char('-')), digit1)), str::parse::<Int>)(input)
+}
+
+/// Parse numbers separated by spaces, such as `  1 -2   3`
+pub fn numbers(input: &str) -> IResult<&str, Vec<Int>> {
+    preceded(space0, separated_list1(space1, number))(input)
+}
+
+/// Parse numbers separated by commas, such as `1,-2, 3`
+pub fn comma_separated_numbers(input: &str) -> IResult<&str, Vec<Int>> {
+    separated_list1(pair(char(','), space0), number)(input)
+}
+
+/// Parse three numbers separated by `separator`, such as `1,2,3`
+pub fn triple<'a>(
+    separator: &'static str,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (Int, Int, Int)> {
+    tuple((
+        terminated(number, tag(separator)),
+        terminated(number, tag(separator)),
+        number,
+    ))
+}
+
+/// Parse the number of a `key=value` field, such as `x=787`
+pub fn field<'a>(key: &'static str) -> impl FnMut(&'a str) -> IResult<&'a str, Int> {
+    preceded(pair(tag(key), char('=')), number)
+}
+
+/// Parse lines that each match `parser`
+pub fn lines<'a, O>(
+    parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    separated_list1(line_ending, parser)
+}
+
+/// Parse an empty line, after the end of a line
+pub fn blank_line(input: &str) -> IResult<&str, ()> {
+    map(pair(line_ending, line_ending), |_| ())(input)
+}
+
+/// Parse blocks separated by blank lines, that each match `parser`
+pub fn blocks<'a, O>(
+    parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    separated_list1(blank_line, parser)
+}
+
+/// Parse lines of characters into a Grid, up to the first empty line
+pub fn grid(input: &str) -> IResult<&str, Grid<char>> {
+    let row = map(take_till1(|c| c == '\n' || c == '\r'), |s: &str| {
+        s.chars().collect()
+    });
+    separated_list1(line_ending, row)(input)
+}
+
+/// Parse a section labelled `label:`, such as `seeds: 1 2 3`, with its content on the same or
+/// the next lines
+pub fn section<'a, O>(
+    label: &'static str,
+    parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O> {
+    preceded(tuple((tag(label), char(':'), multispace0)), parser)
+}
+
+/// Parse a section with any label, such as `seed-to-soil map:` followed by lines of numbers,
+/// returning the label and the content
+pub fn labelled<'a, O>(
+    parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (&'a str, O)> {
+    separated_pair(
+        take_till1(|c| c == ':' || c == '\n'),
+        pair(char(':'), multispace0),
+        parser,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{AocParse, Parse};
+    use crate::test;
+
+    /// A draw of cubes, such as `3 blue`
+    #[derive(AocParse, PartialEq, Debug)]
+    struct Draw {
+        count: Int,
+        color: String,
+    }
+
+    /// A game, such as `Game 1: 3 blue, 4 red; 1 red`
+    #[derive(AocParse, PartialEq, Debug)]
+    #[aoc(prefix = "Game")]
+    struct Game {
+        #[aoc(suffix = ":")]
+        id: Int,
+        #[aoc(separator = ";", separator = ",")]
+        draws: Vec<Vec<Draw>>,
+    }
+
+    #[test]
+    fn test_parsing() {
+        test!(-12, parse_all("-12", number).unwrap());
+        test!(vec![1, -2, 3], parse_all("  1 -2   3", numbers).unwrap());
+        test!(
+            vec![1, -2, 3],
+            parse_all("1,-2, 3", comma_separated_numbers).unwrap()
+        );
+        test!((1, 0, -3), parse_all("1,0,-3", triple(",")).unwrap());
+        test!(787, parse_all("x=787", field("x")).unwrap());
+        test!(
+            vec![vec![vec![1, 2], vec![3]], vec![vec![4]]],
+            parse_all("1 2\n3\n\n4\n", blocks(lines(numbers))).unwrap()
+        );
+        test!(
+            vec![vec!['#', '.'], vec!['.', '#']],
+            parse_all("#.\n.#", grid).unwrap()
+        );
+        test!(
+            vec![79, 14],
+            parse_all("seeds: 79 14", section("seeds", numbers)).unwrap()
+        );
+        test!(
+            ("seed-to-soil map", vec![vec![50, 98, 2]]),
+            parse_all("seed-to-soil map:\n50 98 2", labelled(lines(numbers))).unwrap()
+        );
+
+        let error = ParseError {
+            day: None,
+            line: 2,
+            column: 3,
+            snippet: "x4".to_string(),
+            expected: "end of input".to_string(),
+        };
+        test!(
+            error.clone(),
+            parse_all("1 2\n3 x4", lines(numbers)).unwrap_err()
+        );
+        test!(
+            "Invalid input of day 5, at line 2, column 3: expected end of input, found 'x4'.",
+            error.in_day(5).to_string()
+        );
+        test!(
+            "Invalid input at line 1, column 1: expected Digit, found 'x=y'.",
+            parse_all("x=y", number).unwrap_err().to_string()
+        );
+        test!(
+            "Invalid input of day 19, as a whole: expected a workflow named 'in'.",
+            ParseError::of_whole_input("a workflow named 'in'")
+                .in_day(19)
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_derive_aoc_parse() {
+        let draw = |count, color: &str| Draw {
+            count,
+            color: color.to_string(),
+        };
+        let game = Game {
+            id: 1,
+            draws: vec![vec![draw(3, "blue"), draw(4, "red")], vec![draw(1, "red")]],
+        };
+        test!(
+            game,
+            Game::parse("Game 1: 3 blue, 4 red; 1 red".to_string()).unwrap()
+        );
+        test!(
+            2,
+            parse_all("Game 1: 2 red\nGame 2: 1 blue", lines(Game::parse_record))
+                .unwrap()
+                .len()
+        );
+        test!(
+            "Invalid input at line 1, column 15: expected end of input, found '; red'.",
+            Game::parse("Game 1: 3 blue; red".to_string())
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
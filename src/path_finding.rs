@@ -1,7 +1,9 @@
 // This module contains a generic implementation of the Dijkstra pathfinding algorithm,
 // because these kind of problems will often occurs in Advent of Code
+#![allow(dead_code)]
 
-use crate::{debug, test};
+use crate::test;
+use log::{trace, warn};
 use std::{
     cell::RefCell,
     fmt::{Debug, Display},
@@ -198,11 +200,7 @@ impl<T: PartialEq + Clone + Debug + Display> Graph<T> {
     /// Visit a `NodeRef<T>` that is assumed to be valid, meaning that it exists, is unvisited, and has a distance value.
     /// Will update all unvisited neighbours of the `Node` with the shortest distance to those `Nodes`, or panic
     fn visit_valid_node_ref(&mut self, node_ref: NodeRef<T>) {
-        debug!(
-            true,
-            "visit_valid_node_ref(state: {:?})",
-            node_ref.borrow().state
-        );
+        trace!("visit_valid_node_ref(state: {:?})", node_ref.borrow().state);
         // Remove current_node from unvisited_nodes, and add to visited_nodes.
         self.unvisited_nodes
             .retain(|node| *node.borrow() != *node_ref.borrow());
@@ -282,7 +280,7 @@ impl<T: PartialEq + Clone + Debug + Display> Graph<T> {
         }
         // We have now visited all unvisited Nodes that were reachable
         if !self.unvisited_nodes.is_empty() {
-            println!(
+            warn!(
                 "Not all Nodes have been visited, '{}' are unreachable, this should not occur.",
                 self.unvisited_nodes.len()
             );
@@ -373,7 +371,7 @@ fn test_case_a() {
     test!(0, graph.unvisited_nodes.len());
     test!(7, graph.visited_nodes.len());
     // Test distances
-    let distances = vec![
+    let distances = [
         ("a", 0),
         ("b", 3),
         ("c", 5),
@@ -478,7 +476,7 @@ fn test_case_c() {
     test!(0, graph.unvisited_nodes.len());
     test!(6, graph.visited_nodes.len());
     // Test distances
-    let distances = vec![("S", 0), ("A", 6), ("B", 15), ("C", 11), ("D", 8), ("E", 7)];
+    let distances = [("S", 0), ("A", 6), ("B", 15), ("C", 11), ("D", 8), ("E", 7)];
     distances.iter().for_each(|t| {
         graph.test_distance(t.0, t.1);
     });
@@ -495,3 +493,76 @@ fn test_case_c() {
         graph.test_path(t.0, t.1);
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// A number of nodes, and directed edges between them
+    fn graphs() -> impl Strategy<Value = (usize, Vec<(u8, u8, Distance)>)> {
+        (1..8usize).prop_flat_map(|nodes| {
+            let node = 0..nodes as u8;
+            let edges = prop::collection::vec((node.clone(), node, 0..20 as Distance), 0..20);
+            (Just(nodes), edges)
+        })
+    }
+
+    /// All shortest distances by Floyd–Warshall, `None` if there is no path
+    fn floyd_warshall(nodes: usize, edges: &[(u8, u8, Distance)]) -> Vec<Vec<DistanceOption>> {
+        let mut distances = vec![vec![None; nodes]; nodes];
+        for (i, row) in distances.iter_mut().enumerate() {
+            row[i] = Some(0);
+        }
+        for &(a, b, distance) in edges {
+            let current = &mut distances[a as usize][b as usize];
+            *current = Some(current.map_or(distance, |d: Distance| d.min(distance)));
+        }
+        for k in 0..nodes {
+            for i in 0..nodes {
+                for j in 0..nodes {
+                    if let (Some(a), Some(b)) = (distances[i][k], distances[k][j]) {
+                        let current = &mut distances[i][j];
+                        *current = Some(current.map_or(a + b, |d| d.min(a + b)));
+                    }
+                }
+            }
+        }
+        distances
+    }
+
+    proptest! {
+        #[test]
+        fn dijkstra_matches_floyd_warshall((nodes, edges) in graphs()) {
+            let mut graph: Graph<u8> = Graph::new(0);
+            graph.add_edges(edges.clone());
+            graph.run_pathfinding_algorithm();
+            let expected = floyd_warshall(nodes, &edges);
+            for state in 0..nodes as u8 {
+                let Some(node_ref) = graph.get_node_ref(state) else {
+                    prop_assert_eq!(None, expected[0][state as usize]);
+                    continue;
+                };
+                let node = node_ref.borrow();
+                prop_assert_eq!(expected[0][state as usize], node.distance_option);
+                if let Some(distance) = node.distance_option {
+                    // The path has to be made of edges, and be as long as the distance
+                    prop_assert_eq!(Some(&0), node.path.first());
+                    prop_assert_eq!(Some(&state), node.path.last());
+                    let length: Option<Distance> = node
+                        .path
+                        .windows(2)
+                        .map(|step| {
+                            edges
+                                .iter()
+                                .filter(|(a, b, _)| (*a, *b) == (step[0], step[1]))
+                                .map(|(_, _, d)| *d)
+                                .min()
+                        })
+                        .sum();
+                    prop_assert_eq!(Some(distance), length);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,98 @@
+// Fit polynomials to samples and extrapolate them, exactly in i128. Samples taken at x = 0, 1,
+// 2, ... use finite differences, samples at any x use Lagrange interpolation.
+
+use crate::line_segment::Rational;
+
+/// The table of finite differences of `values`: the values, their differences, the differences of
+/// those, and so on, until a row is all zeros or a single value
+pub fn differences<T: Copy + Into<i128>>(values: &[T]) -> Vec<Vec<i128>> {
+    let mut table: Vec<Vec<i128>> = vec![values.iter().map(|&v| v.into()).collect()];
+    loop {
+        let last = table.last().unwrap();
+        if last.len() <= 1 || last.iter().all(|v| *v == 0) {
+            return table;
+        }
+        let next = last.windows(2).map(|pair| pair[1] - pair[0]).collect();
+        table.push(next);
+    }
+}
+
+/// The value after the last of `values`, on the polynomial of lowest degree through them
+pub fn extrapolate<T: Copy + Into<i128>>(values: &[T]) -> i128 {
+    differences(values)
+        .iter()
+        .filter_map(|row| row.last())
+        .sum()
+}
+
+/// The value before the first of `values`, on the polynomial of lowest degree through them
+pub fn extrapolate_backward<T: Copy + Into<i128>>(values: &[T]) -> i128 {
+    differences(values)
+        .iter()
+        .rev()
+        .filter_map(|row| row.first())
+        .fold(0, |previous, first| first - previous)
+}
+
+/// The value at `x` of the polynomial of lowest degree through `values`, taken at x = 0, 1, 2, ...
+/// using Newton's forward difference formula: the sum of `Δᵏ(values[0]) * (x choose k)`
+/// See: https://en.wikipedia.org/wiki/Newton_polynomial
+pub fn extrapolate_at<T: Copy + Into<i128>>(values: &[T], x: i128) -> i128 {
+    let mut binomial = 1;
+    let mut value = 0;
+    for (k, row) in differences(values).iter().enumerate() {
+        if k > 0 {
+            // (x choose k) = (x choose k - 1) * (x - k + 1) / k, which divides exactly
+            binomial = binomial * (x - k as i128 + 1) / k as i128;
+        }
+        value += row.first().unwrap_or(&0) * binomial;
+    }
+    value
+}
+
+/// The value at `x` of the polynomial of lowest degree through `points`, which may be taken at
+/// any distinct x, as an exact fraction
+/// See: https://en.wikipedia.org/wiki/Lagrange_polynomial
+pub fn lagrange(points: &[(i128, i128)], x: i128) -> Rational {
+    let (mut numerator, mut denominator) = (0, 1);
+    for (i, &(x_i, y_i)) in points.iter().enumerate() {
+        let (mut term_numerator, mut term_denominator) = (y_i, 1);
+        for (j, &(x_j, _)) in points.iter().enumerate() {
+            if i != j {
+                assert!(x_i != x_j, "Invalid points: x = {} occurs twice.", x_i);
+                term_numerator *= x - x_j;
+                term_denominator *= x_i - x_j;
+            }
+        }
+        let sum = Rational::new(
+            numerator * term_denominator + term_numerator * denominator,
+            denominator * term_denominator,
+        );
+        (numerator, denominator) = (sum.numerator, sum.denominator);
+    }
+    Rational::new(numerator, denominator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+
+    #[test]
+    fn test_extrapolation() {
+        let squares: Vec<i64> = (0..5).map(|x| x * x + 1).collect();
+        test!(vec![2, 2, 2], differences(&squares)[2]);
+        test!(26, extrapolate(&squares));
+        test!(2, extrapolate_backward(&squares));
+        test!(10_001, extrapolate_at(&squares, 100));
+        test!(26, extrapolate_at(&squares, -5));
+        test!(7, extrapolate(&[7]));
+        test!(7, extrapolate_at(&[7i32], 1_000));
+
+        let points = [(1, 2), (3, 10), (-2, 5)];
+        test!(Rational::new(26, 1), lagrange(&points, 5));
+        test!(Some(1), lagrange(&points, 0).to_int());
+        let half = lagrange(&[(0, 0), (2, 1)], 1);
+        test!("1/2", half.to_string());
+    }
+}
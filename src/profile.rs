@@ -0,0 +1,207 @@
+// Everyone gets different puzzle inputs, so a solution that works for one account's input can
+// still fail on another's. A profile is a named set of inputs with their answers, stored as:
+//
+//   inputs/<profile>/YYYY_DD.txt   puzzle input for a day
+//   inputs/<profile>/answers.txt   one `YYYY_DD <part one> <part two>` line per day, `?` if unknown
+
+use crate::solution::*;
+use crate::SolutionBox;
+use std::fs::{read_dir, read_to_string};
+use std::path::PathBuf;
+
+/// Directory containing one subdirectory per profile
+pub static PROFILES_DIRECTORY: &str = "inputs";
+/// The profile using the aocf cache and the answers hardcoded in each `Solution`
+pub static DEFAULT_PROFILE: &str = "default";
+/// Run every profile
+pub static ALL_PROFILES: &str = "all";
+
+type Answers = HashMap<(Year, Day), (Option<Answer>, Option<Answer>)>;
+
+pub struct Profile {
+    pub name: String,
+    /// `None` for the default profile
+    directory: Option<PathBuf>,
+    answers: Answers,
+}
+
+impl Profile {
+    pub fn default_profile() -> Profile {
+        Profile {
+            name: DEFAULT_PROFILE.to_string(),
+            directory: None,
+            answers: Answers::new(),
+        }
+    }
+
+    /// Load the profile with the specified name from `PROFILES_DIRECTORY`, which cannot be
+    /// `ALL_PROFILES`, since that selects all profiles
+    pub fn load(name: &str) -> Profile {
+        if name == DEFAULT_PROFILE {
+            return Profile::default_profile();
+        }
+        assert!(
+            name != ALL_PROFILES,
+            "Invalid profile name: '{}', which is reserved for running all profiles.",
+            name
+        );
+        let directory = PathBuf::from(PROFILES_DIRECTORY).join(name);
+        assert!(
+            directory.is_dir(),
+            "No such profile: '{}'.",
+            directory.display()
+        );
+        let answers_path = directory.join("answers.txt");
+        let answers = match read_to_string(&answers_path) {
+            Ok(answers) => parse_answers(&answers),
+            Err(_) => Answers::new(),
+        };
+        Profile {
+            name: name.to_string(),
+            directory: Some(directory),
+            answers,
+        }
+    }
+
+    /// Load the default profile, followed by all profiles in `PROFILES_DIRECTORY` in alphabetical order
+    pub fn load_all() -> Vec<Profile> {
+        let mut names: Vec<String> = match read_dir(PROFILES_DIRECTORY) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| name != DEFAULT_PROFILE)
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        names.sort();
+        once(Profile::default_profile())
+            .chain(names.iter().map(|name| Profile::load(name)))
+            .collect()
+    }
+
+    /// Load the profiles selected by `--profile`
+    pub fn select(name: &str) -> Vec<Profile> {
+        if name == ALL_PROFILES {
+            Profile::load_all()
+        } else {
+            vec![Profile::load(name)]
+        }
+    }
+
+    fn get_input(&self, year: Year, day: Day) -> Option<Input> {
+        match &self.directory {
+            Some(directory) => {
+                read_to_string(directory.join(format!("{}_{:02}.txt", year, day))).ok()
+            }
+            None => Some(aoc::get(year, day)),
+        }
+    }
+
+    /// Run a `Solution` against the input of this profile.
+    /// Returns `None` if this profile has no input for the day of the solution.
+    pub fn run(&self, solution: &SolutionBox) -> Option<TestResult> {
+        let (year, day) = (solution.year(), solution.day());
+        let input = self.get_input(year, day)?;
+        let (expect_part_one, expect_part_two) = match &self.directory {
+            Some(_) => self
+                .answers
+                .get(&(year, day))
+                .cloned()
+                .unwrap_or((None, None)),
+            None => (
+                Some(solution.expect_part_one()),
+                Some(solution.expect_part_two()),
+            ),
+        };
+        Some(solution.run_with_input(input, expect_part_one, expect_part_two))
+    }
+}
+
+/// Parse the lines of an answers file: `YYYY_DD <part one> <part two>`, where `#` starts a comment
+fn parse_answers(answers: &str) -> Answers {
+    let parse_answer = |answer: &str| -> Option<Answer> {
+        match answer {
+            "?" => None,
+            _ => Some(
+                answer
+                    .parse::<Answer>()
+                    .unwrap_or_else(|_| panic!("Invalid answer: '{}'.", answer)),
+            ),
+        }
+    };
+    answers
+        .lines()
+        .map(|line| line.split('#').next().unwrap().trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            assert!(fields.len() == 3, "Invalid answers line: '{}'.", line);
+            let (year, day) = fields[0]
+                .split_once('_')
+                .unwrap_or_else(|| panic!("Invalid day: '{}'.", fields[0]));
+            let key = (year.parse().unwrap(), day.parse().unwrap());
+            (key, (parse_answer(fields[1]), parse_answer(fields[2])))
+        })
+        .collect()
+}
+
+/// A row of the matrix: a day, with a cell for each profile, which is None without its input
+pub type MatrixRow = ((Year, Day), Vec<Option<TestResult>>);
+
+/// Display width of a cell in the matrix: three emoji of two columns wide, separated by spaces
+const CELL_WIDTH: usize = 8;
+
+/// Print a matrix with a row for each day, and a column for each profile, where a profile without
+/// input for the day has ➖ in its cell
+pub fn print_matrix(profiles: &[Profile], results: &[MatrixRow]) {
+    let width = profiles
+        .iter()
+        .map(|p| p.name.len())
+        .max()
+        .unwrap_or(0)
+        .max(CELL_WIDTH);
+    let header: String = profiles
+        .iter()
+        .map(|p| format!(" {:^width$} |", p.name, width = width))
+        .collect();
+    println!("|   Day   |{}", header);
+    println!(
+        "|---------|{}",
+        format!("{}|", "-".repeat(width + 2)).repeat(profiles.len())
+    );
+    for ((year, day), row) in results {
+        let cells: String = row
+            .iter()
+            .map(|result| {
+                let cell = match result {
+                    Some(result) => [&result.examples, &result.p1, &result.p2]
+                        .map(TestStatus::symbol)
+                        .join(" "),
+                    None => [TestStatus::Unknown.symbol(); 3].join(" "),
+                };
+                format!(" {}{} |", cell, " ".repeat(width - CELL_WIDTH))
+            })
+            .collect();
+        println!("| {}-{:02} |{}", year, day, cells);
+    }
+    println!("Legend: [Ex P1 P2] ✅ Success, ❌ Failed, 💥 Error, ❔ Unverified, ➖ No input");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        let answers = parse_answers(
+            "# Answers for a test profile\n2023_01 55386 54824\n\n2023_21 3853 ? # Part two unknown\n",
+        );
+        test!(2, answers.len());
+        test!(Some(&(Some(55386), Some(54824))), answers.get(&(2023, 1)));
+        test!(
+            Some(&(Some(3853), None::<Answer>)),
+            answers.get(&(2023, 21))
+        );
+    }
+}
@@ -0,0 +1,105 @@
+// A small seeded random number generator for generating puzzle inputs, so the same seed always
+// gives the same input, without depending on a crate whose output may change between versions.
+
+use aoc::Int;
+
+/// SplitMix64: fast, and good enough for generating inputs, but not for cryptography
+///
+/// See: https://prng.di.unimi.it/splitmix64.c
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`, panics if `n` is 0
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "Invalid range: '0..{}'.", n);
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// A number in `start..=end`, panics if the range is empty
+    pub fn range(&mut self, start: Int, end: Int) -> Int {
+        assert!(start <= end, "Invalid range: '{}..={}'.", start, end);
+        start + (self.next_u64() % (end - start + 1) as u64) as Int
+    }
+
+    /// True with a probability of `p`
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    /// A random element of `items`, panics if it is empty
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    /// A word of `length` lowercase letters
+    pub fn word(&mut self, length: usize) -> String {
+        (0..length)
+            .map(|_| (b'a' + self.below(26) as u8) as char)
+            .collect()
+    }
+
+    /// Lines of `columns` chars, `rows` of them, with each char picked by `cell`
+    pub fn grid(
+        &mut self,
+        columns: usize,
+        rows: usize,
+        mut cell: impl FnMut(&mut Rng) -> char,
+    ) -> String {
+        let mut result = String::new();
+        for _ in 0..rows {
+            result.extend((0..columns).map(|_| cell(self)));
+            result.push('\n');
+        }
+        result
+    }
+
+    /// Shuffle `items` in place, with Fisher-Yates
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test;
+
+    #[test]
+    fn test_rng() {
+        let numbers = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..5).map(|_| rng.next_u64()).collect::<Vec<_>>()
+        };
+        test!(numbers(42), numbers(42));
+        test!(false, numbers(42) == numbers(43));
+        // The first output for seed 0, from the reference implementation
+        test!(0xe220a8397b1dcdafu64, numbers(0)[0]);
+
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            test!(true, rng.below(3) < 3);
+            test!(true, (-2..=2).contains(&rng.range(-2, 2)));
+        }
+        let mut items: Vec<usize> = (0..10).collect();
+        rng.shuffle(&mut items);
+        items.sort();
+        test!((0..10).collect::<Vec<_>>(), items);
+    }
+}
@@ -0,0 +1,190 @@
+// Generate a new day module from a template, and wire it up in `y<year>/mod.rs` and `aoc.rs`,
+// so adding a day is a single `cargo run -- new --year <year> --day <day>`.
+
+use crate::solution::*;
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::Path;
+
+static LIB_PATH: &str = "src/aoc.rs";
+
+/// Template for a new day module, `{year}`, `{day}` and `{title}` are replaced
+static DAY_TEMPLATE: &str = r#"use crate::*;
+
+/// Advent of Code {year}, day {day}{title}
+///
+/// See: https://adventofcode.com/{year}/day/{day}
+#[derive(Default)]
+pub struct Problem {}
+
+impl Solution for Problem {
+    fn year(&self) -> Year {
+        {year}
+    }
+    fn day(&self) -> Day {
+        {day}
+    }
+    fn expect_part_one(&self) -> Answer {
+        0
+    }
+    fn expect_part_two(&self) -> Answer {
+        0
+    }
+
+    define_examples! {
+        (
+            "
+            ",
+            Expect::Any,
+        )
+    }
+
+    fn solve_part_one(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let lines: Vec<String> = InputLines::from(input).filter_empty_lines().into();
+        Ok(lines.len() as Answer)
+    }
+
+    fn solve_part_two(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let lines: Vec<String> = InputLines::from(input).filter_empty_lines().into();
+        Ok(lines.len() as Answer)
+    }
+}
+"#;
+
+/// Fill in the day template, adding the puzzle title if there is one
+fn generate_day_module(year: Year, day: Day, title: Option<String>) -> String {
+    let title = match title {
+        Some(title) => format!(": {}", title),
+        None => String::new(),
+    };
+    DAY_TEMPLATE
+        .replace("{year}", &year.to_string())
+        .replace("{day}", &day.to_string())
+        .replace("{title}", &title)
+}
+
+/// Insert `line` into the sorted block of lines in `source` that start with `prefix`
+/// (ignoring indentation), keeping the block sorted. Does nothing if `line` is already there.
+/// Panics if there is no such block.
+fn insert_sorted(source: &str, prefix: &str, line: &str) -> String {
+    let mut lines: Vec<&str> = source.lines().collect();
+    if lines.iter().any(|l| l.trim() == line) {
+        return source.to_string();
+    }
+    let block: Vec<usize> = (0..lines.len())
+        .filter(|&i| lines[i].trim_start().starts_with(prefix))
+        .collect();
+    assert!(
+        !block.is_empty(),
+        "No lines starting with '{}' found.",
+        prefix
+    );
+    let indentation =
+        &lines[block[0]][..lines[block[0]].len() - lines[block[0]].trim_start().len()];
+    let position = block
+        .iter()
+        .find(|&&i| lines[i].trim() > line)
+        .copied()
+        .unwrap_or(block.last().unwrap() + 1);
+    let indented_line = format!("{}{}", indentation, line);
+    lines.insert(position, &indented_line);
+    lines.join("\n") + "\n"
+}
+
+/// Read a file, apply `edit` to its contents, and write it back
+fn edit_file(path: &Path, edit: impl Fn(&str) -> String) {
+    let source =
+        read_to_string(path).unwrap_or_else(|_| panic!("Cannot read: '{}'.", path.display()));
+    write(path, edit(&source)).unwrap();
+}
+
+/// Generate `src/y<year>/d<day>.rs`, and wire it up in `y<year>/mod.rs` and `aoc.rs`.
+/// An existing, non-empty day module is only overwritten if `force` is set.
+pub fn new_day(year: Year, day: Day, force: bool) {
+    assert!((1..=25).contains(&day), "Invalid day: '{}'.", day);
+    let year_module = format!("y{}", year);
+    let day_module = format!("d{:02}", day);
+    let directory = Path::new("src").join(&year_module);
+    let day_path = directory.join(format!("{}.rs", day_module));
+    let mod_path = directory.join("mod.rs");
+
+    let existing = read_to_string(&day_path).unwrap_or_default();
+    assert!(
+        existing.trim().is_empty() || force,
+        "Day module already exists: '{}', use --force to overwrite it.",
+        day_path.display()
+    );
+
+    create_dir_all(&directory).unwrap();
+    if !mod_path.exists() {
+        write(&mod_path, "").unwrap();
+        edit_file(Path::new(LIB_PATH), |lib| {
+            insert_sorted(lib, "pub mod ", &format!("pub mod {};", year_module))
+        });
+    }
+
+    let title = aoc::get_title(year, day);
+    write(&day_path, generate_day_module(year, day, title)).unwrap();
+    println!("Generated: '{}'.", day_path.display());
+
+    edit_file(&mod_path, |modules| {
+        let line = format!("pub mod {};", day_module);
+        if modules.trim().is_empty() {
+            line + "\n"
+        } else {
+            insert_sorted(modules, "pub mod ", &line)
+        }
+    });
+    edit_file(Path::new(LIB_PATH), |lib| {
+        insert_sorted(
+            lib,
+            "y20",
+            &format!("{}::{}::Problem::create_box(),", year_module, day_module),
+        )
+    });
+    println!(
+        "Wired up {}::{} in '{}' and '{}'.",
+        year_module,
+        day_module,
+        mod_path.display(),
+        LIB_PATH
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_sorted() {
+        let source = "mod grid;\nmod y2023;\n\nfn main() {\n    let v = vec![\n        y2023::d01::Problem::create_box(),\n        y2023::d03::Problem::create_box(),\n    ];\n}\n";
+        let with_day = insert_sorted(source, "y20", "y2023::d02::Problem::create_box(),");
+        test!(
+            "mod grid;\nmod y2023;\n\nfn main() {\n    let v = vec![\n        y2023::d01::Problem::create_box(),\n        y2023::d02::Problem::create_box(),\n        y2023::d03::Problem::create_box(),\n    ];\n}\n",
+            with_day
+        );
+        test!(
+            with_day.clone(),
+            insert_sorted(&with_day, "y20", "y2023::d02::Problem::create_box(),")
+        );
+        let with_year = insert_sorted(source, "mod ", "mod y2024;");
+        test!(
+            true,
+            with_year.starts_with("mod grid;\nmod y2023;\nmod y2024;\n")
+        );
+    }
+
+    #[test]
+    fn test_generate_day_module() {
+        let module = generate_day_module(2023, 1, Some("Trebuchet?!".to_string()));
+        test!(
+            true,
+            module.contains("/// Advent of Code 2023, day 1: Trebuchet?!\n")
+        );
+        test!(
+            true,
+            module.contains("fn day(&self) -> Day {\n        1\n    }")
+        );
+        let module = generate_day_module(2024, 7, None);
+        test!(true, module.contains("/// Advent of Code 2024, day 7\n"));
+    }
+}
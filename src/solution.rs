@@ -9,12 +9,18 @@ pub use nom::multi::separated_list1;
 pub use nom::sequence::{preceded, terminated, tuple};
 pub use nom::IResult;
 
+pub use crate::parsing::{ParseError, Record};
+pub use crate::random::Rng;
+pub use aoc_derive::AocParse;
+pub use log::{debug, error, info, log_enabled, trace, warn, Level};
+
 use colored::Colorize;
 pub use std::collections::HashMap;
 use std::fmt::write;
 pub use std::fmt::Debug;
 use std::iter;
 pub use std::iter::once;
+use std::panic::{self, AssertUnwindSafe};
 use std::time::Duration;
 pub use std::time::Instant;
 
@@ -26,42 +32,238 @@ pub type ExampleInput = &'static str;
 
 use colored::*;
 
-/// Use the newtype pattern to implement `From` and `Into` for `Input` and `Vec<String>`. \
-/// `InputLines` is only a wrapper for `Vec<String>`.
+/// The lines of an `Input`, with helpers for the shapes inputs come in: paragraphs separated by
+/// blank lines, numbers per line, whitespace-separated columns and grids of chars.
 ///
-/// See: https://doc.rust-lang.org/book/ch19-03-advanced-traits.html#using-the-newtype-pattern-to-implement-external-traits-on-external-types
-pub struct InputLines(Vec<String>);
+/// It remembers where its lines start in the `Input` it was split from, so errors point at the
+/// right line, and whether that ended with a newline, so it converts back to it losslessly.
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct InputLines {
+    lines: Vec<String>,
+    /// The index of the first line in the Input it was split from
+    first_line: usize,
+    trailing_newline: bool,
+}
 
 impl InputLines {
     pub fn filter_empty_lines(self) -> InputLines {
-        InputLines(self.0.into_iter().filter(|line| !line.is_empty()).collect())
+        InputLines {
+            lines: self
+                .lines
+                .into_iter()
+                .filter(|line| !line.is_empty())
+                .collect(),
+            ..self
+        }
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Split into the paragraphs separated by blank lines, skipping empty paragraphs
+    pub fn paragraphs(self) -> Vec<InputLines> {
+        let mut result = Vec::new();
+        let mut current = InputLines::default();
+        for (i, line) in self.lines.into_iter().enumerate() {
+            if line.trim().is_empty() {
+                if !current.is_empty() {
+                    result.push(current);
+                }
+                current = InputLines::default();
+            } else {
+                if current.is_empty() {
+                    current.first_line = self.first_line + i;
+                }
+                current.lines.push(line);
+            }
+        }
+        if !current.is_empty() {
+            result.push(current);
+        }
+        result
+    }
+
+    /// The signed numbers in each line, ignoring any other text, such as `[79, 14]` for
+    /// `seeds: 79 14`. A `-` is only a sign if it is not part of a word, as in `x-to-y 3`. Fails
+    /// on the first number that does not fit in an Int.
+    pub fn numbers(&self) -> Result<Vec<Vec<Int>>, ParseError> {
+        self.lines
+            .iter()
+            .enumerate()
+            .map(|(row, line)| {
+                numbers_in(line).map_err(|(column, i)| ParseError {
+                    day: None,
+                    line: self.first_line + row + 1,
+                    column: column + 1,
+                    snippet: line[i..].to_string(),
+                    expected: "a number that fits in 64 bits".to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// The whitespace-separated fields of the lines, column by column
+    pub fn columns(&self) -> Vec<Vec<String>> {
+        let mut columns: Vec<Vec<String>> = Vec::new();
+        for line in &self.lines {
+            for (i, field) in line.split_whitespace().enumerate() {
+                if i == columns.len() {
+                    columns.push(Vec::new());
+                }
+                columns[i].push(field.to_string());
+            }
+        }
+        columns
+    }
+
+    /// An error for the whole line at `index`, such as a line with too few numbers. Only points at
+    /// the right line if no empty lines were filtered out.
+    pub fn error_at(&self, index: usize, expected: impl Into<String>) -> ParseError {
+        ParseError {
+            day: None,
+            line: self.first_line + index + 1,
+            column: 1,
+            snippet: self.lines[index].clone(),
+            expected: expected.into(),
+        }
+    }
+
+    /// Run `parser` on the text of these lines, with errors pointing at the line in the Input
+    pub fn parse_with<O>(
+        &self,
+        parser: impl FnMut(&str) -> IResult<&str, O>,
+    ) -> Result<O, ParseError> {
+        let text = self.lines.join("\n");
+        crate::parsing::parse_all(&text, parser).map_err(|e| ParseError {
+            line: e.line + self.first_line,
+            ..e
+        })
+    }
+
+    /// Convert to a Grid with a row per line, or fail on the first char that `T` cannot be converted from
+    pub fn try_into_grid<T: CharConvertable>(self) -> Result<Grid<T>, ParseError> {
+        self.lines
+            .iter()
+            .enumerate()
+            .map(|(row, line)| {
+                line.char_indices()
+                    .enumerate()
+                    .map(|(column, (i, c))| {
+                        T::try_from_char(c).ok_or_else(|| ParseError {
+                            day: None,
+                            line: self.first_line + row + 1,
+                            column: column + 1,
+                            snippet: line[i..].to_string(),
+                            expected: format!("{} char", with_article(T::type_name())),
+                        })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Check that the lines are a rectangle of chars, or fail on the first line that is not as long
+    /// as the first
+    pub fn check_rectangular(&self) -> Result<(), ParseError> {
+        let width = self.lines.first().map_or(0, |line| line.chars().count());
+        match (0..self.len()).find(|&i| self.lines[i].chars().count() != width) {
+            Some(row) => Err(self.error_at(row, format!("a row of {} chars", width))),
+            None => Ok(()),
+        }
+    }
+
+    /// Convert to a Map with a row per line, or fail on the first char that `T` cannot be converted
+    /// from, or the first line that is not as long as the first
+    pub fn try_into_map<T: CharConvertable>(self) -> Result<crate::grid::Map<T>, ParseError> {
+        self.check_rectangular()?;
+        Ok(self.try_into_grid()?.into())
     }
 }
 
+/// Prefix `noun` with `a`, or with `an` if it starts with a vowel: `an Instruction`
+fn with_article(noun: &str) -> String {
+    match noun.chars().next() {
+        Some(c) if "aeiouAEIOU".contains(c) => format!("an {}", noun),
+        _ => format!("a {}", noun),
+    }
+}
+
+/// The signed numbers in `line`, where a `-` directly after a letter or digit is not a sign, or
+/// the column and byte index of the first number that does not fit in an Int
+fn numbers_in(line: &str) -> Result<Vec<Int>, (usize, usize)> {
+    let mut result = Vec::new();
+    // The column and byte index where the current number starts
+    let mut current: Option<(usize, usize)> = None;
+    let mut previous = ' ';
+    let end = (line.chars().count(), (line.len(), ' '));
+    for (column, (i, c)) in line.char_indices().enumerate().chain(once(end)) {
+        if c.is_ascii_digit() || (c == '-' && current.is_none() && !previous.is_alphanumeric()) {
+            current.get_or_insert((column, i));
+        } else if let Some((start_column, start)) = current.take() {
+            let number = &line[start..i];
+            if number != "-" {
+                result.push(number.parse().map_err(|_| (start_column, start))?);
+            }
+        }
+        previous = c;
+    }
+    Ok(result)
+}
+
 impl Debug for InputLines {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:#?}", self.0)
+        write!(f, "{:#?}", self.lines)
     }
 }
 
-/// Make Input convertible to InputLines(Vec<String>) by lines()
+/// Make Input convertible to InputLines by lines()
 impl From<Input> for InputLines {
     fn from(input: Input) -> Self {
-        InputLines(input.lines().map(String::from).collect())
+        InputLines {
+            lines: input.lines().map(String::from).collect(),
+            first_line: 0,
+            trailing_newline: input.ends_with('\n'),
+        }
+    }
+}
+
+/// Make InputLines convertible back to the Input it came from, which is lossless for `\n` line
+/// endings
+impl From<InputLines> for Input {
+    fn from(input: InputLines) -> Self {
+        let mut result = input.lines.join("\n");
+        if input.trailing_newline {
+            result.push('\n');
+        }
+        result
     }
 }
 
 /// Make InputLines convertible to Vec<String>
 impl Into<Vec<String>> for InputLines {
     fn into(self) -> Vec<String> {
-        self.0
+        self.lines
     }
 }
 
-/// Make InputLines convertible to Grid<char>
-impl Into<Grid<char>> for InputLines {
-    fn into(self) -> Grid<char> {
-        self.0.into_iter().map(|s| s.chars().collect()).collect()
+/// Make InputLines convertible to a Grid of chars, or of anything else that is CharConvertable,
+/// panics on a char that `T` cannot be converted from
+impl<T: CharConvertable> From<InputLines> for Grid<T> {
+    fn from(input: InputLines) -> Self {
+        input
+            .lines
+            .into_iter()
+            .map(|s| s.chars().map(T::from_char).collect())
+            .collect()
     }
 }
 
@@ -85,11 +287,68 @@ fn trim_example_input(input: ExampleInput) -> Input {
 #[derive(PartialEq, Eq)]
 pub enum TestStatus {
     Failed(Duration, Answer),
-    Error(Duration),
+    /// The solver panicked or could not parse its input, with the error message
+    Error(Duration, String),
     Success(Duration, Answer),
+    /// There was no expected answer to compare the answer to
+    Unverified(Duration, Answer),
     Unknown,
 }
 
+impl TestStatus {
+    /// Compare the result of a solver run to the expected answer, if there is one
+    fn from_result(
+        duration: Duration,
+        result: Result<Answer, String>,
+        expected: Option<Answer>,
+    ) -> TestStatus {
+        match (result, expected) {
+            (Err(message), _) => TestStatus::Error(duration, message),
+            (Ok(answer), None) => TestStatus::Unverified(duration, answer),
+            (Ok(answer), Some(expected)) if answer == expected => {
+                TestStatus::Success(duration, answer)
+            }
+            (Ok(answer), Some(_)) => TestStatus::Failed(duration, answer),
+        }
+    }
+
+    /// A single character summary of this TestStatus, used in reports
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Failed(_, _) => "❌",
+            Self::Error(_, _) => "💥",
+            Self::Success(_, _) => "✅",
+            Self::Unverified(_, _) => "❔",
+            Self::Unknown => "➖",
+        }
+    }
+
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::Failed(_, _) | Self::Error(_, _))
+    }
+}
+
+/// Turn the result of a solver run that may have panicked, or failed to parse the input of `day`,
+/// into an `Err` with a message
+fn flatten_result<T>(result: Result<Result<T, ParseError>, String>, day: Day) -> Result<T, String> {
+    result.and_then(|result| result.map_err(|e| e.in_day(day).to_string()))
+}
+
+/// Run `f`, catching any panic and turning it into an `Err` with the panic message
+fn catch_panic<T>(f: impl FnOnce() -> T) -> (Duration, Result<T, String>) {
+    let instant = Instant::now();
+    let result = panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "Unknown panic".to_string()
+        }
+    });
+    (instant.elapsed(), result)
+}
+
 impl Debug for TestStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -102,7 +361,13 @@ impl Debug for TestStatus {
                     duration
                 )
             }
-            Self::Error(duration) => write!(f, "[TestStatus::{}] {:.2?}", "Error".red(), duration),
+            Self::Error(duration, message) => write!(
+                f,
+                "[TestStatus::{}] [{}] {:.2?}",
+                "Error".red(),
+                message,
+                duration
+            ),
             Self::Success(duration, answer) => {
                 write!(
                     f,
@@ -112,6 +377,15 @@ impl Debug for TestStatus {
                     duration
                 )
             }
+            Self::Unverified(duration, answer) => {
+                write!(
+                    f,
+                    "[TestStatus::{}] [{}] {:.2?}",
+                    "Unverified".yellow(),
+                    answer,
+                    duration
+                )
+            }
             Self::Unknown => write!(f, "[TestStatus::Unknown]"),
         }
     }
@@ -123,6 +397,16 @@ pub struct TestResult {
     pub p1: TestStatus,
     pub p2: TestStatus,
     pub examples: TestStatus,
+    /// Records logged while running, if they were captured
+    pub logs: Vec<String>,
+}
+
+impl TestResult {
+    pub fn is_failure(&self) -> bool {
+        [&self.examples, &self.p1, &self.p2]
+            .iter()
+            .any(|status| status.is_failure())
+    }
 }
 
 impl Debug for TestResult {
@@ -139,13 +423,50 @@ impl Debug for TestResult {
     }
 }
 
+/// What a solver is run against: the puzzle input, or an example with its parameters
+#[derive(Debug, Default, Clone)]
+pub struct Context {
+    example: bool,
+    parameters: HashMap<&'static str, Int>,
+}
+
+impl Context {
+    pub fn puzzle() -> Context {
+        Context::default()
+    }
+
+    pub fn example(parameters: &[(&'static str, Int)]) -> Context {
+        Context {
+            example: true,
+            parameters: parameters.iter().copied().collect(),
+        }
+    }
+
+    pub fn is_example(&self) -> bool {
+        self.example
+    }
+
+    /// Return parameter `name` of an example, or `default` if it is not set, e.g. for the puzzle input
+    pub fn get_or<T>(&self, name: &str, default: T) -> T
+    where
+        T: TryFrom<Int>,
+        T::Error: Debug,
+    {
+        match self.parameters.get(name) {
+            Some(&value) => T::try_from(value)
+                .unwrap_or_else(|e| panic!("Invalid parameter: '{}' = {}, {:?}.", name, value, e)),
+            None => default,
+        }
+    }
+}
+
 /// Trait for implementing an Advent of Code problem
 pub trait Solution {
-    /// Solve AoC(`YEAR`, `DAY`) part one
-    fn solve_part_one(&self, input: Input, is_example: bool) -> Answer;
+    /// Solve AoC(`YEAR`, `DAY`) part one, or fail on a malformed input
+    fn solve_part_one(&self, input: Input, context: &Context) -> Result<Answer, ParseError>;
 
-    /// Solve AoC(`YEAR`, `DAY`) part two
-    fn solve_part_two(&self, input: Input, is_example: bool) -> Answer;
+    /// Solve AoC(`YEAR`, `DAY`) part two, or fail on a malformed input
+    fn solve_part_two(&self, input: Input, context: &Context) -> Result<Answer, ParseError>;
 
     fn year(&self) -> Year;
 
@@ -160,78 +481,99 @@ pub trait Solution {
         Vec::new()
     }
 
-    fn run_part_one(&self) -> Answer {
+    /// Parse `input` like the solvers do, without solving, so that any malformed input is reported
+    /// as a ParseError instead of a panic. The fuzz targets call this. The default is for days
+    /// that accept any text. If one part needs more of the input than the other, this accepts what
+    /// either part can solve, and the stricter part reports its own error.
+    fn parse_input(&self, _input: Input) -> Result<(), ParseError> {
+        Ok(())
+    }
+
+    /// Generate a random valid input, which grows with `size`, for stress and differential
+    /// testing. `None` if there is no generator for this day.
+    fn generate_input(&self, _rng: &mut Rng, _size: usize) -> Option<Input> {
+        None
+    }
+
+    fn run_part_one(&self) -> Result<Answer, ParseError> {
         let input = aoc::get(self.year(), self.day());
-        let solution = self.solve_part_one(input, false);
+        let solution = self.solve_part_one(input, &Context::puzzle());
 
         solution
     }
 
-    fn run_part_two(&self) -> Answer {
+    fn run_part_two(&self) -> Result<Answer, ParseError> {
         let input = aoc::get(self.year(), self.day());
-        let solution = self.solve_part_two(input, false);
+        let solution = self.solve_part_two(input, &Context::puzzle());
 
         solution
     }
 
     /// Run all given examples
-    fn run_examples(&self) -> bool {
-        let format = |part: usize| {
-            format!("[Ex] [{}] [{}] [{}]", self.year(), self.day(), part)
-        };
+    fn run_examples(&self) -> Result<bool, ParseError> {
+        let format = |part: usize| format!("[Ex] [{}] [{}] [{}]", self.year(), self.day(), part);
 
         for (i, example) in self.define_examples().iter().enumerate() {
-            let input = trim_example_input(example.input);
+            let input = example.get_input();
+            let context = &Context::example(example.parameters);
             match example.expect {
                 Expect::PartOne(one) => {
-                    test!(one, self.solve_part_one(input, true), format(1));
+                    test!(one, self.solve_part_one(input, context)?, format(1));
                 }
                 Expect::PartTwo(two) => {
-                    test!(two, self.solve_part_two(input, true), format(2));
+                    test!(two, self.solve_part_two(input, context)?, format(2));
                 }
                 Expect::PartsOneAndTwo(one, two) => {
-                    test!(
-                        one,
-                        self.solve_part_one(input.clone(), true),
-                        format(1)
-                    );
-                    test!(two, self.solve_part_two(input, true), format(2));
+                    test!(one, self.solve_part_one(input.clone(), context)?, format(1));
+                    test!(two, self.solve_part_two(input, context)?, format(2));
                 }
                 Expect::Any => (),
             }
         }
-        true
+        Ok(true)
     }
 
     fn run(&self) -> TestResult {
-        let mut test_result: TestResult = TestResult {
-            day: self.day(),
-            year: self.year(),
-            p1: TestStatus::Unknown,
-            p2: TestStatus::Unknown,
-            examples: TestStatus::Unknown,
-        };
-        let mut instant = Instant::now();
-        test_result.examples = match self.run_examples() {
-            true => TestStatus::Success(instant.elapsed(), 1),
-            false => TestStatus::Failed(instant.elapsed(), 0),
-        };
+        let input = aoc::get(self.year(), self.day());
+        self.run_with_input(
+            input,
+            Some(self.expect_part_one()),
+            Some(self.expect_part_two()),
+        )
+    }
 
-        instant = Instant::now();
-        let mut answer = self.run_part_one();
-        test_result.p1 = match answer == self.expect_part_one() {
-            true => TestStatus::Success(instant.elapsed(), answer),
-            false => TestStatus::Failed(instant.elapsed(), answer),
+    /// Run all examples, and solve both parts for `input`, comparing them to the expected answers.
+    /// Panics and malformed inputs are reported as `TestStatus::Error`, so one broken solution does not stop a whole run.
+    fn run_with_input(
+        &self,
+        input: Input,
+        expect_part_one: Option<Answer>,
+        expect_part_two: Option<Answer>,
+    ) -> TestResult {
+        let (duration, examples) = catch_panic(|| self.run_examples());
+        let examples = match flatten_result(examples, self.day()) {
+            Ok(true) => TestStatus::Success(duration, 1),
+            Ok(false) => TestStatus::Failed(duration, 0),
+            Err(message) => TestStatus::Error(duration, message),
         };
 
-        instant = Instant::now();
-        answer = self.run_part_two();
-        test_result.p2 = match answer == self.expect_part_two() {
-            true => TestStatus::Success(instant.elapsed(), answer),
-            false => TestStatus::Failed(instant.elapsed(), answer),
-        };
+        let (duration, answer) =
+            catch_panic(|| self.solve_part_one(input.clone(), &Context::puzzle()));
+        let answer = flatten_result(answer, self.day());
+        let p1 = TestStatus::from_result(duration, answer, expect_part_one);
+
+        let (duration, answer) = catch_panic(|| self.solve_part_two(input, &Context::puzzle()));
+        let answer = flatten_result(answer, self.day());
+        let p2 = TestStatus::from_result(duration, answer, expect_part_two);
 
-        test_result
+        TestResult {
+            day: self.day(),
+            year: self.year(),
+            p1,
+            p2,
+            examples,
+            logs: Vec::new(),
+        }
     }
 
     fn create_box() -> Box<Self>
@@ -242,14 +584,9 @@ pub trait Solution {
     }
 }
 
-/// Trait to allow a type to be parsed from Problem Input
-pub trait Parse {
-    fn parse(input: Input) -> Self;
-}
-
-/// Parse a single number
-pub fn parse_num(input: &str) -> IResult<&str, Int> {
-    map_res(digit1, str::parse::<Int>)(input)
+/// Trait to allow a type to be parsed from Problem Input, which may be malformed
+pub trait Parse: Sized {
+    fn parse(input: Input) -> Result<Self, ParseError>;
 }
 
 /// Advent of Code ExampleInput expectation for Problem part one, part two, or both
@@ -260,38 +597,283 @@ pub enum Expect {
     Any,
 }
 
+impl Expect {
+    /// Parse the sidecar file of a fixture file, e.g. `examples/2023/05_a.expect` for
+    /// `examples/2023/05_a.txt`, with `part_one = <answer>` and `part_two = <answer>` lines.
+    /// Returns `Expect::Any` if it has neither.
+    pub fn parse_sidecar(sidecar: &str) -> Expect {
+        let (mut one, mut two) = (None, None);
+        for line in sidecar.lines() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .unwrap_or_else(|| panic!("Invalid sidecar line: '{}'.", line));
+            let answer: Answer = value
+                .trim()
+                .parse()
+                .unwrap_or_else(|_| panic!("Invalid answer: '{}'.", value.trim()));
+            match key.trim() {
+                "part_one" => one = Some(answer),
+                "part_two" => two = Some(answer),
+                key => panic!("Invalid sidecar key: '{}'.", key),
+            }
+        }
+        match (one, two) {
+            (Some(one), Some(two)) => Expect::PartsOneAndTwo(one, two),
+            (Some(one), None) => Expect::PartOne(one),
+            (None, Some(two)) => Expect::PartTwo(two),
+            (None, None) => Expect::Any,
+        }
+    }
+}
+
 /// Advent of Code ExampleInput and expectation
 pub struct Example {
     pub input: ExampleInput,
     pub expect: Expect,
+    /// Inline examples are indented string literals that are trimmed, fixture files are used as is
+    pub verbatim: bool,
+    /// Parameters passed to the solvers through their `Context`, e.g. `("steps", 6)`
+    pub parameters: &'static [(&'static str, Int)],
 }
 
 impl Example {
     pub fn get_input(&self) -> Input {
-        trim_example_input(self.input)
+        match self.verbatim {
+            true => self.input.to_string(),
+            false => trim_example_input(self.input),
+        }
     }
 }
 
-/// Define Advent of Code Examples
+/// Define Advent of Code Examples, each either an inline string literal with its `Expect`, or a
+/// fixture file relative to the crate root, with its `Expect` or one read from its sidecar file:
+/// `(file("examples/2023/05_a.txt"),)`. Examples can end with parameters for the solvers, which
+/// they read from their `Context`: `("...", Expect::PartOne(16), steps = 6,)`
 #[macro_export]
 macro_rules! define_examples {
     (
         $(
             (
-                $input:expr,
-                $expect:expr,
+                $($example:tt)*
             )
         ),* $(,)?
     ) => {
         fn define_examples(&self) -> Vec<Example> {
             vec![
                 $(
-                    Example {
-                        input: $input,
-                        expect: $expect,
-                    },
+                    $crate::define_example!($($example)*),
                 )*
             ]
         }
     };
 }
+
+/// Define a single Advent of Code Example, see `define_examples!`
+#[macro_export]
+macro_rules! define_example {
+    (file($path:literal), $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::define_example!(@example
+            ::aoc_derive::include_fixture!($path),
+            Expect::parse_sidecar(::aoc_derive::include_sidecar!($path)),
+            true,
+            $($name = $value),*
+        )
+    };
+    (file($path:literal), $expect:expr $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::define_example!(@example
+            ::aoc_derive::include_fixture!($path),
+            $expect,
+            true,
+            $($name = $value),*
+        )
+    };
+    (file($path:literal) $(,)?) => {
+        $crate::define_example!(@example
+            ::aoc_derive::include_fixture!($path),
+            Expect::parse_sidecar(::aoc_derive::include_sidecar!($path)),
+            true,
+        )
+    };
+    (@example $input:expr, $expect:expr, $verbatim:expr, $($name:ident = $value:expr),*) => {
+        Example {
+            input: $input,
+            expect: $expect,
+            verbatim: $verbatim,
+            parameters: &[$((stringify!($name), $value)),*],
+        }
+    };
+    ($input:expr, $expect:expr $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::define_example!(@example $input, $expect, false, $($name = $value),*)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example_input() {
+        let inline = define_example!(
+            "\n            a\n\n              b\n            ",
+            Expect::Any
+        );
+        test!("a\n\nb", inline.get_input());
+        let fixture = define_example!(file("examples/2023/05_a.txt"));
+        test!(
+            true,
+            fixture.get_input().starts_with("seeds: 79 14 55 13\n\n")
+        );
+        test!(true, fixture.get_input().ends_with("56 93 4\n"));
+        test!(
+            true,
+            matches!(fixture.expect, Expect::PartsOneAndTwo(35, 46))
+        );
+        let with_parameters =
+            define_example!("\n            a\n            ", Expect::Any, steps = 6,);
+        let context = Context::example(with_parameters.parameters);
+        test!(6usize, context.get_or("steps", 64));
+        test!(10, context.get_or("expansion", 10));
+        test!(64usize, Context::puzzle().get_or("steps", 64));
+    }
+
+    #[test]
+    fn test_parse_sidecar() {
+        let expect = Expect::parse_sidecar("# Example a\npart_one = 35\npart_two = 46\n");
+        test!(true, matches!(expect, Expect::PartsOneAndTwo(35, 46)));
+        test!(
+            true,
+            matches!(Expect::parse_sidecar("part_two = -1"), Expect::PartTwo(-1))
+        );
+        test!(true, matches!(Expect::parse_sidecar(""), Expect::Any));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        let d02 = crate::y2023::d02::Problem::default();
+        let input = "Game 1: 1 red, 2 green, 3 blue\nGame two".to_string();
+        let result = d02.run_with_input(input, Some(1), None);
+        let expected =
+            "Invalid input of day 2, at line 2, column 1: expected end of input, found 'Game two'.";
+        test!(false, result.examples.is_failure());
+        test!(
+            true,
+            matches!(result.p1, TestStatus::Error(_, ref message) if message == expected)
+        );
+        test!(true, result.is_failure());
+    }
+
+    #[test]
+    fn test_fuzz_regressions() {
+        // Inputs on which a parser or solver used to panic, which must now fail with a ParseError,
+        // or parse and solve
+        for day in crate::solutions() {
+            let directory = format!("fuzz/regressions/d{:02}", day.day());
+            let Ok(entries) = std::fs::read_dir(directory) else {
+                continue;
+            };
+            for entry in entries {
+                let path = entry.unwrap().path();
+                let input = std::fs::read_to_string(&path).unwrap();
+                let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                    if day.parse_input(input.clone()).is_ok() {
+                        let _ = day.solve_part_one(input.clone(), &Context::puzzle());
+                        let _ = day.solve_part_two(input, &Context::puzzle());
+                    }
+                }));
+                test!(true, result.is_ok(), "{}", path.display());
+            }
+        }
+    }
+
+    #[test]
+    fn test_generated_inputs() {
+        use crate::y2023::*;
+        let days: Vec<Box<dyn Solution>> = vec![
+            d10::Problem::create_box(),
+            d11::Problem::create_box(),
+            d12::Problem::create_box(),
+            d16::Problem::create_box(),
+            d18::Problem::create_box(),
+            d19::Problem::create_box(),
+            d20::Problem::create_box(),
+            d22::Problem::create_box(),
+        ];
+        for day in days {
+            for seed in 0..5 {
+                let generate = || day.generate_input(&mut Rng::new(seed), 6).unwrap();
+                test!(generate(), generate());
+                let context = Context::puzzle();
+                test!(true, day.solve_part_one(generate(), &context).is_ok());
+                test!(true, day.solve_part_two(generate(), &context).is_ok());
+            }
+        }
+        // The loop of day 10 visits 8 tiles around each of the 27 cells of its spanning tree, and
+        // encloses the tile in the middle of each
+        let d10 = d10::Problem::default();
+        let input = || d10.generate_input(&mut Rng::new(0), 6).unwrap();
+        test!(
+            108,
+            d10.solve_part_one(input(), &Context::puzzle()).unwrap()
+        );
+        test!(27, d10.solve_part_two(input(), &Context::puzzle()).unwrap());
+        test!(
+            None::<Input>,
+            d01::Problem::default().generate_input(&mut Rng::new(0), 6)
+        );
+    }
+
+    #[test]
+    fn test_input_lines() {
+        let input = "seeds: 79 -14\n\nx-to-y map:\n50 98 2\n52 50 48\n\n\n^>\nv?\n".to_string();
+        let paragraphs = InputLines::from(input.clone()).paragraphs();
+        test!(3, paragraphs.len());
+        test!(vec![vec![79, -14]], paragraphs[0].numbers().unwrap());
+        test!(
+            vec![vec![], vec![50, 98, 2], vec![52, 50, 48]],
+            paragraphs[1].numbers().unwrap()
+        );
+        test!(
+            "Invalid input at line 1, column 8: expected a number that fits in 64 bits, found \
+             '99999999999999999999 14'.",
+            InputLines::from("seeds: 99999999999999999999 14".to_string())
+                .numbers()
+                .unwrap_err()
+                .to_string()
+        );
+        test!(
+            vec![
+                vec!["x-to-y", "50", "52"],
+                vec!["map:", "98", "50"],
+                vec!["2", "48"]
+            ],
+            paragraphs[1].columns()
+        );
+        test!(
+            "Invalid input at line 9, column 2: expected a Direction char, found '?'.",
+            paragraphs[2]
+                .clone()
+                .try_into_map::<crate::grid::Direction>()
+                .map(|_| ())
+                .unwrap_err()
+                .to_string()
+        );
+        test!("an Instruction", with_article("Instruction"));
+        test!(
+            "Invalid input at line 3, column 1: expected Digit, found 'x-to-y map:'.",
+            paragraphs[1]
+                .parse_with(|s| crate::parsing::lines(crate::parsing::numbers)(s))
+                .unwrap_err()
+                .to_string()
+        );
+        let round_trip = Input::from(InputLines::from(input.clone()));
+        test!(input, round_trip);
+        test!(
+            "a\n\nb",
+            Input::from(InputLines::from("a\n\nb".to_string()))
+        );
+    }
+}
@@ -1,10 +1,36 @@
 use crate::*;
 
-fn get_calibration_value(input: &String) -> Int {
+fn get_calibration_value(input: &String) -> Option<Int> {
     let digits: Vec<_> = input.chars().filter(|c| c.is_digit(10)).collect();
-    format!("{}{}", digits.first().unwrap(), digits.last().unwrap())
+    format!("{}{}", digits.first()?, digits.last()?)
         .parse::<Int>()
-        .unwrap()
+        .ok()
+}
+
+/// The calibration value of every line, with `spelled` digits such as `one` replaced by digits
+fn get_calibration_values(input: Input, spelled: bool) -> Result<Vec<Int>, ParseError> {
+    let lines = InputLines::from(input);
+    let replace = |line: &String| {
+        line.replace("one", "o1e")
+            .replace("two", "t2o")
+            .replace("three", "t3e")
+            .replace("four", "f4r")
+            .replace("five", "f5e")
+            .replace("six", "s6x")
+            .replace("seven", "s7n")
+            .replace("eight", "e8t")
+            .replace("nine", "n9e")
+    };
+    lines
+        .lines()
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| {
+            let line = if spelled { replace(line) } else { line.clone() };
+            get_calibration_value(&line).ok_or_else(|| lines.error_at(i, "a digit"))
+        })
+        .collect()
 }
 
 #[derive(Default)]
@@ -27,29 +53,15 @@ impl Solution for Problem {
         54824
     }
 
-    fn solve_part_one(&self, input: Input, _is_example: bool) -> Answer {
-        let input: Vec<String> = InputLines::from(input).filter_empty_lines().into();
-        let solution = input.iter().map(|line| get_calibration_value(line)).sum();
-        solution
+    fn parse_input(&self, input: Input) -> Result<(), ParseError> {
+        get_calibration_values(input, false).map(|_| ())
+    }
+
+    fn solve_part_one(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        Ok(get_calibration_values(input, false)?.into_iter().sum())
     }
 
-    fn solve_part_two(&self, input: Input, _is_example: bool) -> Answer {
-        let input: Vec<String> = InputLines::from(input).filter_empty_lines().into();
-        let solution = input
-            .iter()
-            .map(|line| {
-                line.replace("one", "o1e")
-                    .replace("two", "t2o")
-                    .replace("three", "t3e")
-                    .replace("four", "f4r")
-                    .replace("five", "f5e")
-                    .replace("six", "s6x")
-                    .replace("seven", "s7n")
-                    .replace("eight", "e8t")
-                    .replace("nine", "n9e")
-            })
-            .map(|line| get_calibration_value(&line))
-            .sum();
-        solution
+    fn solve_part_two(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        Ok(get_calibration_values(input, true)?.into_iter().sum())
     }
 }
@@ -7,9 +7,11 @@ struct Game {
     max_blue: Int,
 }
 
-impl Parse for Game {
-    fn parse(input: Input) -> Self {
-        Game::parse_game(&input).unwrap().1
+type Games = Vec<Game>;
+
+impl Parse for Games {
+    fn parse(input: Input) -> Result<Self, ParseError> {
+        parsing::parse_all(&input, parsing::lines(Game::parse_game))
     }
 }
 
@@ -17,11 +19,11 @@ impl Game {
     /// Use nom to parse a single Game
     fn parse_game(input: &str) -> IResult<&str, Game> {
         let parse_rgb = tuple((
-            parse_num,
+            parsing::number,
             preceded(tag(" "), alt((tag("red"), tag("green"), tag("blue")))),
         ));
 
-        let (rest, id) = preceded(tag("Game "), terminated(parse_num, tag(": ")))(input)?;
+        let (rest, id) = preceded(tag("Game "), terminated(parsing::number, tag(": ")))(input)?;
         let (rest, rgb) = separated_list1(tuple((one_of(",;"), tag(" "))), parse_rgb)(rest)?;
 
         let get_max = |color: &str| {
@@ -29,7 +31,7 @@ impl Game {
                 .filter(|x| x.1 == color)
                 .map(|x| x.0)
                 .max()
-                .unwrap()
+                .unwrap_or(0)
         };
 
         Ok((
@@ -61,24 +63,24 @@ impl Solution for Problem {
         84538
     }
 
-    fn solve_part_one(&self, input: Input, _is_example: bool) -> Answer {
-        let input: Vec<String> = InputLines::from(input).filter_empty_lines().into();
-        let solution = input
+    fn parse_input(&self, input: Input) -> Result<(), ParseError> {
+        Games::parse(input).map(|_| ())
+    }
+
+    fn solve_part_one(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let solution = Games::parse(input)?
             .into_iter()
-            .map(Game::parse)
             .filter(|game| game.max_red <= 12 && game.max_green <= 13 && game.max_blue <= 14)
             .map(|game| game.id)
             .sum();
-        solution
+        Ok(solution)
     }
 
-    fn solve_part_two(&self, input: Input, _is_example: bool) -> Answer {
-        let input: Vec<String> = InputLines::from(input).filter_empty_lines().into();
-        let solution = input
+    fn solve_part_two(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let solution = Games::parse(input)?
             .into_iter()
-            .map(Game::parse)
             .map(|game| game.max_red * game.max_green * game.max_blue)
             .sum();
-        solution
+        Ok(solution)
     }
 }
@@ -6,9 +6,10 @@ enum Adjacent {
     Two,
 }
 
-/// Find the sum of part numbers from a grid of chars where adjacent holds for each symbol
-fn solve(g: Grid<char>, adjacent: Adjacent) -> Int {
-    let mut sum = 0;
+/// Find the sum of part numbers from a grid of chars where adjacent holds for each symbol, or None
+/// if it overflows
+fn solve(g: Grid<char>, adjacent: Adjacent) -> Option<Int> {
+    let mut sum: Int = 0;
     for y_1 in 0..g.len() {
         for x_1 in 0..g[0].len() {
             let current = g[y_1][x_1];
@@ -44,20 +45,39 @@ fn solve(g: Grid<char>, adjacent: Adjacent) -> Int {
                         }
                     }
                 }
-                sum += match adjacent {
-                    Adjacent::Any => adj.iter().sum(),
+                let value = match adjacent {
+                    Adjacent::Any => adj.iter().try_fold(0, |sum: Int, n| sum.checked_add(*n))?,
                     Adjacent::Two => {
                         if adj.len() == 2 {
-                            adj[0] * adj[1]
+                            adj[0].checked_mul(adj[1])?
                         } else {
                             0
                         }
                     }
                 };
+                sum = sum.checked_add(value)?;
             }
         }
     }
-    sum
+    Some(sum)
+}
+
+/// Parse the schematic, which must be rectangular, with numbers that fit in an Int
+fn parse(input: Input) -> Result<Grid<char>, ParseError> {
+    let lines = InputLines::from(input);
+    for (i, line) in lines.lines().iter().enumerate() {
+        let mut numbers = line.split(|c: char| !c.is_ascii_digit());
+        if numbers.any(|n| !n.is_empty() && n.parse::<Int>().is_err()) {
+            return Err(lines.error_at(i, "numbers that fit in 64 bits"));
+        }
+    }
+    lines.check_rectangular()?;
+    Ok(lines.into())
+}
+
+/// The error for a schematic whose answer does not fit in an Int
+fn overflow() -> ParseError {
+    ParseError::of_whole_input("part numbers whose sum fits in 64 bits")
 }
 
 #[derive(Default)]
@@ -95,13 +115,17 @@ impl Solution for Problem {
         )
     }
 
-    fn solve_part_one(&self, input: Input, _is_example: bool) -> Answer {
-        let grid: Grid<char> = InputLines::from(input).into();
-        solve(grid, Adjacent::Any)
+    fn parse_input(&self, input: Input) -> Result<(), ParseError> {
+        parse(input).map(|_| ())
+    }
+
+    fn solve_part_one(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let grid = parse(input)?;
+        solve(grid, Adjacent::Any).ok_or_else(overflow)
     }
 
-    fn solve_part_two(&self, input: Input, _is_example: bool) -> Answer {
-        let grid: Grid<char> = InputLines::from(input).into();
-        solve(grid, Adjacent::Two)
+    fn solve_part_two(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let grid = parse(input)?;
+        solve(grid, Adjacent::Two).ok_or_else(overflow)
     }
 }
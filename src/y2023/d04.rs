@@ -1,32 +1,25 @@
 use crate::*;
-use nom::character::complete::{space0, space1};
 
+/// A Card, such as `Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53`
+#[derive(AocParse)]
+#[aoc(prefix = "Card")]
 struct Card {
+    #[aoc(suffix = ":")]
     id: Int,
     wins: Vec<Int>,
+    #[aoc(prefix = "|")]
     numbers: Vec<Int>,
 }
 
-impl Parse for Card {
-    fn parse(input: Input) -> Self {
-        Card::parse_card(&input).unwrap().1
+type Cards = Vec<Card>;
+
+impl Parse for Cards {
+    fn parse(input: Input) -> Result<Self, ParseError> {
+        parsing::parse_all(&input, parsing::lines(Card::parse_record))
     }
 }
 
 impl Card {
-    /// Parse a single Card
-    fn parse_card(input: &str) -> IResult<&str, Card> {
-        let (rest, id) = preceded(
-            tuple((tag("Card"), space1)),
-            terminated(parse_num, tuple((tag(":"), space1))),
-        )(input)?;
-        let parse_numbers = |input| separated_list1(space1, parse_num)(input);
-        let (rest, wins) = parse_numbers(rest)?;
-        let (rest, _) = tuple((space0, tag("|"), space0))(rest)?;
-        let (rest, numbers) = parse_numbers(rest)?;
-        Ok((rest, Card { id, wins, numbers }))
-    }
-
     /// Get amount of winning numbers
     fn get_matches(&self) -> Int {
         self.numbers
@@ -35,17 +28,23 @@ impl Card {
             .count() as Int
     }
 
-    /// Get the value of this Card, which is 2.pow(self.get_matches()-1) or 0
-    fn get_value(&self) -> Int {
+    /// Get the value of this Card, which is 2.pow(self.get_matches()-1) or 0, or None if it
+    /// overflows
+    fn get_value(&self) -> Option<Int> {
         let matches = self.get_matches();
         if matches > 0 {
-            (2 as Int).pow((matches - 1).try_into().unwrap())
+            (2 as Int).checked_pow((matches - 1).try_into().ok()?)
         } else {
-            0
+            Some(0)
         }
     }
 }
 
+/// The error for cards whose points or number of won cards do not fit in an Int
+fn overflow() -> ParseError {
+    ParseError::of_whole_input("cards whose total fits in 64 bits")
+}
+
 #[derive(Default)]
 pub struct Problem {}
 
@@ -77,27 +76,34 @@ impl Solution for Problem {
         )
     }
 
-    fn solve_part_one(&self, input: Input, _is_example: bool) -> Answer {
-        let lines: Vec<String> = InputLines::from(input).into();
-        let cards: Vec<Card> = lines.into_iter().map(Card::parse).collect();
-        let total = cards.iter().map(Card::get_value).sum();
-        total
+    fn parse_input(&self, input: Input) -> Result<(), ParseError> {
+        Cards::parse(input).map(|_| ())
+    }
+
+    fn solve_part_one(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let cards = Cards::parse(input)?;
+        cards
+            .iter()
+            .try_fold(0, |total: Int, card| total.checked_add(card.get_value()?))
+            .ok_or_else(overflow)
     }
 
-    fn solve_part_two(&self, input: Input, _is_example: bool) -> Answer {
-        let lines: Vec<String> = InputLines::from(input).into();
-        let cards: Vec<Card> = lines.into_iter().map(Card::parse).collect();
+    fn solve_part_two(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let cards = Cards::parse(input)?;
         let mut card_count: HashMap<Int, Int> = HashMap::new();
         for card in &cards {
             let count = *card_count.entry(card.id).or_insert(1);
             for i in 0..card.get_matches() {
-                let won_card = card.id + i + 1;
+                let won_card = card.id.saturating_add(i + 1);
                 if won_card as usize <= cards.len() {
-                    *card_count.entry(won_card).or_insert(1) += count;
+                    let won_count = card_count.entry(won_card).or_insert(1);
+                    *won_count = won_count.checked_add(count).ok_or_else(overflow)?;
                 }
             }
         }
-        let total = card_count.values().sum();
-        total
+        card_count
+            .values()
+            .try_fold(0, |total: Int, &count| total.checked_add(count))
+            .ok_or_else(overflow)
     }
 }
@@ -1,193 +1,105 @@
+use crate::interval::{Interval, IntervalSet};
 use crate::*;
 
 type Almanac = Vec<Vec<Transform>>;
 
-#[derive(Clone)]
-struct Interval {
-    a: Int,
-    b: Int,
-    v: bool,
-}
-
-impl PartialEq for Interval {
-    fn eq(&self, other: &Self) -> bool {
-        self.a == other.a && self.b == other.b
-    }
-}
-
-impl Debug for Interval {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Interval")
-            .field("a", &self.a)
-            .field("b", &self.b)
-            .finish()
-    }
-}
-
-impl Interval {
-    fn new(a: Int, b: Int) -> Interval {
-        assert!(
-            a <= b,
-            "Invalid interval: a ({}) <= b ({}) must hold.",
-            a,
-            b
-        );
-        Interval { a, b, v: false }
-    }
-
-    fn len(&self) -> Int {
-        self.b - self.a
-    }
-
-    fn split(&mut self, at: Int) -> Interval {
-        assert!(at >= self.a && at <= self.b);
-        let split_off_part = Interval::new(at, self.b);
-        self.b = at - 1;
-        split_off_part
-    }
-
-    fn shift(&mut self, by: Int) -> &mut Interval {
-        self.a += by;
-        self.b += by;
-        self
-    }
-
-    fn apply(&self, t: &Transform) -> Vec<Interval> {
-        let mut result: Vec<Interval> = vec![self.clone()];
-        let mut transformed = false;
-
-        if self.a >= t.source.a && self.b <= t.source.b {
-            result[0].shift(t.get_shift());
-            transformed = true;
-        } else {
-            let split_by_source_a = t.source.a > self.a && t.source.a <= self.b;
-            let split_by_source_b = t.source.b < self.b && t.source.b >= self.a;
-
-            if split_by_source_a {
-                let mut first = result.pop().unwrap();
-                let second = first.split(t.source.a);
-                result = vec![first, second];
-                transformed = true;
-            }
-            if split_by_source_b {
-                let mut last = result.pop().unwrap();
-                let second = last.split(t.source.b);
-                result.push(last);
-                result.push(second);
-                transformed = true;
-            }
-            if split_by_source_a && split_by_source_b {
-                result[1].shift(t.get_shift());
-            } else if split_by_source_a {
-                result[1].shift(t.get_shift());
-            } else if split_by_source_b {
-                result[0].shift(t.get_shift());
-            }
-        }
-        if transformed {
-            for i in &mut result {
-                i.v = true;
-            }
-        }
-
-        result
-    }
-}
-
 #[derive(Debug)]
 struct Transform {
     source: Interval,
-    destination: Interval,
+    shift: Int,
 }
 
 impl Transform {
-    fn new(destination: Int, source: Int, length: Int) -> Transform {
-        Transform {
-            source: Interval::new(source, source + length),
-            destination: Interval::new(destination, destination + length),
-        }
-    }
-
-    fn get_shift(&self) -> Int {
-        assert!(self.source.len() == self.destination.len());
-        self.destination.a - self.source.a
+    /// None if the length is negative or any of the values overflow
+    fn new(destination: Int, source: Int, length: Int) -> Option<Transform> {
+        destination.checked_add(length)?;
+        Some(Transform {
+            source: checked_interval(source, length)?,
+            shift: destination.checked_sub(source)?,
+        })
     }
+}
 
-    fn apply(&self, intervals: Vec<Interval>) -> Vec<Interval> {
-        let mut result: Vec<Interval> = Vec::new();
-        for interval in intervals {
-            if !interval.v {
-                let new_intervals = interval.apply(self);
-                result = result
-                    .into_iter()
-                    .chain(new_intervals.into_iter())
-                    .collect();
-            } else {
-                result.push(interval);
-            }
-        }
-        result
-    }
+/// The Interval of `length` from `start`, or None if the length is negative or the end overflows
+fn checked_interval(start: Int, length: Int) -> Option<Interval> {
+    start.checked_add(length)?;
+    (length >= 0).then(|| Interval::with_length(start, length))
 }
 
-fn apply_almanac(s: Vec<Interval>, a: &Almanac) -> Vec<Interval> {
+/// Map the seeds through every map of the almanac. Within a map, each value is transformed by
+/// the first Transform whose source contains it, or kept as is if there is none.
+fn apply_almanac(s: IntervalSet, a: &Almanac) -> IntervalSet {
     let mut s = s;
     for (i, transforms) in a.iter().enumerate() {
+        let mut transformed = IntervalSet::new();
         for t in transforms {
-            let old_s = s.clone();
-            s = t.apply(s);
-            if old_s != s {
-                debug!(false, "({:?},{:?},{:?})", old_s, t, &s);
+            let (inside, outside) = s.split(&t.source.into());
+            if !inside.is_empty() {
+                trace!("({:?},{:?},{:?})", inside, t, outside);
             }
+            transformed = transformed.union(&inside.shift(t.shift));
+            s = outside;
         }
-        debug!(false, "[{}]: {:?}", i, &s);
-        for i in &mut s {
-            i.v = false;
-        }
+        s = s.union(&transformed);
+        trace!("[{}]: {:?}", i, &s);
     }
     s
 }
 
-fn parse(e: &Vec<String>, seeds_is_range: bool) -> (Vec<Interval>, Almanac) {
-    let mut s: Vec<Interval> = Vec::new();
-    let mut a: Almanac = Vec::new();
-    a.push(Vec::new());
-    let mut c = 0;
-    for l in e {
-        if l.contains("seeds:") {
-            let i: Vec<_> = l[6..]
-                .split_whitespace()
-                .map(|x| x.parse::<Int>().unwrap())
-                .collect();
-            if seeds_is_range {
-                for x in 0..i.len() {
-                    if x % 2 == 1 {
-                        continue;
-                    }
-                    s.push(Interval::new(i[x], i[x] + i[x + 1] - 1));
-                }
-            } else {
-                for x in i {
-                    s.push(Interval::new(x, x));
-                }
-            }
-        } else if l.contains("map:") {
-            a.push(Vec::new());
-            if !a[c].is_empty() {
-                c += 1;
-            }
-        } else if l.is_empty() {
-            continue;
-        } else {
-            let i: Vec<_> = l
-                .split_whitespace()
-                .map(|x| x.parse::<Int>().unwrap())
-                .collect();
-
-            a[c].push(Transform::new(i[0], i[1], i[2]));
+/// Parse the seeds, either as single values or as ranges of `start length`, and the maps of the
+/// almanac, which each follow a header line. There must be at least one seed and one map.
+fn parse(input: Input, seeds_is_range: bool) -> Result<(IntervalSet, Almanac), ParseError> {
+    let paragraphs = InputLines::from(input.clone()).paragraphs();
+    let Some((seeds, maps)) = paragraphs.split_first() else {
+        return Err(ParseError::at(&input, "", "seeds"));
+    };
+    if maps.is_empty() {
+        return Err(ParseError::at(&input, "", "a map"));
+    }
+    let numbers = seeds.numbers()?.concat();
+    let mut s = IntervalSet::new();
+    if seeds_is_range {
+        if numbers.len() % 2 != 0 {
+            return Err(seeds.error_at(0, "seed ranges of a start and a length"));
+        }
+        for range in numbers.chunks(2) {
+            let Some(interval) = checked_interval(range[0], range[1]) else {
+                return Err(seeds.error_at(0, "seed ranges of a start and a length"));
+            };
+            s.insert(interval);
+        }
+    } else {
+        for seed in numbers {
+            let Some(interval) = checked_interval(seed, 1) else {
+                return Err(seeds.error_at(0, "seeds"));
+            };
+            s.insert(interval);
         }
     }
-    (s, a)
+    if s.is_empty() {
+        return Err(seeds.error_at(0, "seeds"));
+    }
+    let mut a = Almanac::new();
+    for map in maps {
+        let mut transforms = Vec::new();
+        for (i, numbers) in map.numbers()?.into_iter().enumerate() {
+            let transform = match numbers[..] {
+                [] => continue,
+                [destination, source, length] => Transform::new(destination, source, length),
+                _ => None,
+            };
+            transforms.push(
+                transform.ok_or_else(|| map.error_at(i, "a destination, a source and a length"))?,
+            );
+        }
+        a.push(transforms);
+    }
+    Ok((s, a))
+}
+
+/// The error for an almanac that maps its seeds to no location
+fn no_seeds() -> ParseError {
+    ParseError::of_whole_input("seeds that map to a location")
 }
 
 #[derive(Default)]
@@ -208,60 +120,40 @@ impl Solution for Problem {
     }
 
     define_examples! {
-        (
-            "
-            seeds: 79 14 55 13
-
-            seed-to-soil map:
-            50 98 2
-            52 50 48
-
-            soil-to-fertilizer map:
-            0 15 37
-            37 52 2
-            39 0 15
-
-            fertilizer-to-water map:
-            49 53 8
-            0 11 42
-            42 0 7
-            57 7 4
-
-            water-to-light map:
-            88 18 7
-            18 25 70
-
-            light-to-temperature map:
-            45 77 23
-            81 45 19
-            68 64 13
-
-            temperature-to-humidity map:
-            0 69 1
-            1 0 69
+        (file("examples/2023/05_a.txt"),)
+    }
 
-            humidity-to-location map:
-            60 56 37
-            56 93 4
+    /// Part one reads any seeds, part two also needs them to be ranges, which it reports itself
+    fn parse_input(&self, input: Input) -> Result<(), ParseError> {
+        parse(input, false).map(|_| ())
+    }
 
-            ",
-            Expect::PartsOneAndTwo(35, 46),
-        )
+    fn solve_part_one(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let (s, a) = parse(input, false)?;
+        apply_almanac(s, &a).min().ok_or_else(no_seeds)
     }
 
-    fn solve_part_one(&self, input: Input, _is_example: bool) -> Answer {
-        let input = input.lines().map(|s| s.to_string()).collect(); // Todo: Make Input convertible to Vec<String>, and vice versa
-        let (s, a) = parse(&input, false);
-        let mut s = apply_almanac(s, &a);
-        s.sort_by_key(|i| i.a);
-        s[0].a
+    fn solve_part_two(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let (s, a) = parse(input, true)?;
+        apply_almanac(s, &a).min().ok_or_else(no_seeds)
     }
+}
 
-    fn solve_part_two(&self, input: Input, _is_example: bool) -> Answer {
-        let input = input.lines().map(|s| s.to_string()).collect();
-        let (s, a) = parse(&input, true);
-        let mut s = apply_almanac(s, &a);
-        s.sort_by_key(|i| i.a);
-        s[0].a
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_seeds() {
+        let d05 = Problem::default();
+        let input = "seeds: 79 14 55\n\nseed-to-soil map:\n50 98 2\n".to_string();
+        let context = Context::puzzle();
+        test!(true, d05.parse_input(input.clone()).is_ok());
+        test!(14, d05.solve_part_one(input.clone(), &context).unwrap());
+        test!(
+            "Invalid input at line 1, column 1: expected seed ranges of a start and a length, \
+             found 'seeds: 79 14 55'.",
+            d05.solve_part_two(input, &context).unwrap_err().to_string()
+        );
     }
 }
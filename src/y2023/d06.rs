@@ -1,4 +1,6 @@
 use crate::*;
+use nom::character::complete::{line_ending, space0, space1};
+use nom::sequence::{pair, separated_pair};
 
 type Uint = u64;
 
@@ -6,24 +8,66 @@ type Uint = u64;
 struct Race {
     time: Uint,
     distance: Uint,
-    wins: Vec<Race>,
 }
 
 impl Race {
-    pub fn generate_wins(&mut self) {
-        for i in 0..self.time {
-            let time_left = self.time - i;
-            let speed = i;
-            let distance_travelled = speed * time_left;
-            if distance_travelled > self.distance {
-                self.wins.push(Race {
-                    time: i,
-                    distance: distance_travelled,
-                    wins: Vec::new(),
-                })
-            }
+    /// None if a number does not fit, or is too large to square
+    fn new(time: &str, distance: &str) -> Option<Race> {
+        let time = time.parse::<Uint>().ok()?;
+        let distance = distance.parse::<Uint>().ok()?;
+        time.checked_mul(time)?;
+        distance.checked_mul(4)?;
+        Some(Race { time, distance })
+    }
+
+    /// Holding the button for `hold` ms beats the record if `hold * (time - hold) > distance`, so
+    /// the winning holds lie strictly between the roots `(time ± √(time² - 4 * distance)) / 2`
+    fn number_of_ways_to_win(&self) -> Uint {
+        let (time, distance) = (self.time, self.distance);
+        let wins = |hold: Uint| hold * (time - hold) > distance;
+        let Some(discriminant) = (time * time).checked_sub(4 * distance) else {
+            return 0;
+        };
+        // Rounding the root down starts at or below the lowest winning hold
+        let mut lowest = (time - math::isqrt(discriminant)) / 2;
+        while lowest <= time / 2 && !wins(lowest) {
+            lowest += 1;
         }
+        if lowest > time / 2 {
+            return 0;
+        }
+        // The winning holds are symmetric around time / 2
+        time - 2 * lowest + 1
+    }
+}
+
+/// Parse the digits of the numbers on a line after `label`, such as `Time:      7  15   30`
+fn row<'a>(label: &'static str) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<&'a str>> {
+    preceded(pair(tag(label), space0), separated_list1(space1, digit1))
+}
+
+/// Parse the races, or with `kerning` the single race whose numbers are all digits of a line
+fn parse_races(input: &str, kerning: bool) -> Result<Vec<Race>, ParseError> {
+    let (mut times, mut distances) = parsing::parse_all(
+        input,
+        separated_pair(row("Time:"), line_ending, row("Distance:")),
+    )?;
+    if times.len() != distances.len() {
+        return Err(ParseError::at(input, input, "as many times as distances"));
     }
+    let (time, distance) = (times.concat(), distances.concat());
+    if kerning {
+        (times, distances) = (vec![time.as_str()], vec![distance.as_str()]);
+    }
+    times
+        .into_iter()
+        .zip(distances)
+        .map(|(time, distance)| {
+            Race::new(time, distance).ok_or_else(|| {
+                ParseError::at(input, input, "a time below 2^32 and a distance below 2^62")
+            })
+        })
+        .collect()
 }
 
 #[derive(Default)]
@@ -53,57 +97,22 @@ impl Solution for Problem {
         )
     }
 
-    fn solve_part_one(&self, input: Input, is_example: bool) -> Answer {
-        let input: Vec<String> = input.lines().map(|s| s.to_string()).collect();
-        let times = input.get(0).unwrap().split_ascii_whitespace().skip(1);
-        let distances = input.get(1).unwrap().split_ascii_whitespace().skip(1);
-        let time_distance_tuples = times.zip(distances);
-
-        let mut races: Vec<Race> = Vec::new();
-
-        for (time, distance) in time_distance_tuples {
-            println!("({}, {})", time, distance);
-            let mut race = Race {
-                time: time.to_string().parse::<Uint>().unwrap(),
-                distance: distance.to_string().parse::<Uint>().unwrap(),
-                wins: Vec::new(),
-            };
-            race.generate_wins();
-            races.push(race);
-        }
-        debug!(is_example, &races);
-        let mut number_of_ways_to_beat_record = 0;
-        for race in &races {
-            if race.wins.len() > 0 && number_of_ways_to_beat_record == 0 {
-                number_of_ways_to_beat_record = 1;
-            }
-            number_of_ways_to_beat_record *= race.wins.len();
-        }
-
-        number_of_ways_to_beat_record as Answer
+    fn parse_input(&self, input: Input) -> Result<(), ParseError> {
+        parse_races(&input, false)?;
+        parse_races(&input, true).map(|_| ())
     }
 
-    fn solve_part_two(&self, input: Input, _is_example: bool) -> Answer {
-        let input: Vec<String> = input.lines().map(|s| s.to_string()).collect();
-        let convert_to_number = |s: &String| -> Uint {
-            s.split_ascii_whitespace()
-                .skip(1)
-                .collect::<Vec<&str>>()
-                .join("")
-                .to_string()
-                .parse::<Uint>()
-                .unwrap()
-        };
-        let time = convert_to_number(input.get(0).unwrap());
-        let distance = convert_to_number(input.get(1).unwrap());
+    fn solve_part_one(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let races = parse_races(&input, false)?;
+        debug!("{:#?}", races);
+        let number_of_ways_to_beat_record: Uint =
+            races.iter().map(Race::number_of_ways_to_win).product();
+        Ok(number_of_ways_to_beat_record as Answer)
+    }
 
-        let mut race: Race = Race {
-            time,
-            distance,
-            wins: Vec::new(),
-        };
-        race.generate_wins();
-        let number_of_ways_to_beat_second_record = race.wins.len();
-        number_of_ways_to_beat_second_record as Answer
+    fn solve_part_two(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let race = parse_races(&input, true)?.remove(0);
+        let number_of_ways_to_beat_second_record = race.number_of_ways_to_win();
+        Ok(number_of_ways_to_beat_second_record as Answer)
     }
 }
@@ -63,8 +63,7 @@ fn cards_from_string(cards: String) -> Option<Cards> {
     if cards.len() == 5 {
         let mut result: Cards = [Card::_2, Card::_2, Card::_2, Card::_2, Card::_2];
         for (i, c) in cards.chars().enumerate() {
-            let card = Card::from_char(c).unwrap();
-            result[i] = card;
+            result[i] = Card::from_char(c)?;
         }
         return Some(result);
     }
@@ -190,14 +189,14 @@ impl PartialOrd for Hand {
 }
 
 impl Hand {
-    fn from_string(hand: &str) -> Hand {
-        let cards: Cards = cards_from_string(String::from(hand)).unwrap();
-        let cards_type: CardsType = CardsType::from_cards(&cards).unwrap();
-        Hand {
+    fn from_string(hand: &str) -> Option<Hand> {
+        let cards: Cards = cards_from_string(String::from(hand))?;
+        let cards_type: CardsType = CardsType::from_cards(&cards)?;
+        Some(Hand {
             to_string: String::from(hand),
             cards,
             cards_type,
-        }
+        })
     }
 
     fn replace_all_j_with_joker(&self) -> Hand {
@@ -231,24 +230,31 @@ impl PartialOrd for Play {
 }
 
 impl Play {
-    fn from_tuple(play: &(&str, &str)) -> Play {
+    fn from_tuple(play: &(&str, &str)) -> Option<Play> {
         let (cards, bid) = play;
-        let hand = Hand::from_string(cards);
-        let bid = bid.to_string().parse::<u32>().unwrap();
-        Play { hand, bid }
+        let hand = Hand::from_string(cards)?;
+        let bid = bid.to_string().parse::<u32>().ok()?;
+        Some(Play { hand, bid })
     }
 }
 
 type Plays = Vec<Play>;
 
 impl Parse for Plays {
-    fn parse(input: Input) -> Self {
-        input
+    fn parse(input: Input) -> Result<Self, ParseError> {
+        let lines = InputLines::from(input);
+        lines
             .lines()
-            .map(|s| s.split_ascii_whitespace().collect::<Vec<_>>())
-            .filter(|v| v.len() == 2)
-            .map(|t| (t[0], t[1]))
-            .map(|t| Play::from_tuple(&t))
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                match line.split_ascii_whitespace().collect::<Vec<_>>()[..] {
+                    [cards, bid] => Play::from_tuple(&(cards, bid)),
+                    _ => None,
+                }
+                .ok_or_else(|| lines.error_at(i, "a hand of five cards and a bid"))
+            })
             .collect()
     }
 }
@@ -292,15 +298,19 @@ impl Solution for Problem {
         )
     }
 
-    fn solve_part_one(&self, input: Input, _is_example: bool) -> Answer {
-        let mut plays = Plays::parse(input);
+    fn parse_input(&self, input: Input) -> Result<(), ParseError> {
+        Plays::parse(input).map(|_| ())
+    }
+
+    fn solve_part_one(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let mut plays = Plays::parse(input)?;
         plays.sort();
         let total_winnings = get_total_winnings(&plays);
-        total_winnings as Answer
+        Ok(total_winnings as Answer)
     }
 
-    fn solve_part_two(&self, input: Input, _is_example: bool) -> Answer {
-        let mut plays = Plays::parse(input);
+    fn solve_part_two(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let mut plays = Plays::parse(input)?;
         plays = plays
             .iter()
             .map(|play| Play {
@@ -310,6 +320,6 @@ impl Solution for Problem {
             .collect();
         plays.sort();
         let total_winnings_with_jokers = get_total_winnings(&plays);
-        total_winnings_with_jokers as Answer
+        Ok(total_winnings_with_jokers as Answer)
     }
 }
@@ -1,4 +1,6 @@
 use crate::*;
+use std::collections::HashSet;
+use std::iter::successors;
 
 #[derive(Debug)]
 enum Instruction {
@@ -20,11 +22,17 @@ impl Instruction {
 type Instructions = Vec<Instruction>;
 
 impl Parse for Instructions {
-    fn parse(input: Input) -> Self {
-        let first_line = input.lines().next().unwrap();
+    fn parse(input: Input) -> Result<Self, ParseError> {
+        let first_line = input.lines().next().unwrap_or_default();
+        if first_line.is_empty() {
+            return Err(ParseError::at(&input, &input, "'L' or 'R'"));
+        }
         first_line
-            .chars()
-            .map(|c| Instruction::from_char(c))
+            .char_indices()
+            .map(|(i, c)| match c {
+                'L' | 'R' => Ok(Instruction::from_char(c)),
+                _ => Err(ParseError::at(&input, &input[i..], "'L' or 'R'")),
+            })
             .collect()
     }
 }
@@ -40,7 +48,7 @@ struct Node {
 
 impl Node {
     fn from_string(string: &str) -> Option<Node> {
-        if string.len() != 16 {
+        if string.len() != 16 || !string.is_ascii() || &string[3..7] != " = (" {
             return None;
         }
         let from_string = string.to_string();
@@ -56,20 +64,6 @@ impl Node {
     }
 }
 
-// Return the greatest common multiple of a and b
-fn gcd(a: u64, b: u64) -> u64 {
-    if b == 0 {
-        a
-    } else {
-        gcd(b, a % b)
-    }
-}
-
-/// Return the least common multiple of a and b
-fn lcm(a: u64, b: u64) -> u64 {
-    a * b / gcd(a, b)
-}
-
 type StepsFromNode<'a> = (&'a Node, i32);
 
 #[derive(Debug)]
@@ -78,8 +72,32 @@ struct Network {
 }
 
 impl Parse for Network {
-    fn parse(input: Input) -> Self {
-        Network::from_strings(&input.lines().map(|s| s.to_string()).collect())
+    fn parse(input: Input) -> Result<Self, ParseError> {
+        // The first line holds the Instructions, and every other line that is not empty a Node
+        let lines = InputLines::from(input);
+        let nodes: Vec<(usize, Node)> = lines
+            .lines()
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(i, line)| {
+                Node::from_string(line)
+                    .map(|node| (i, node))
+                    .ok_or_else(|| lines.error_at(i, "a node like 'AAA = (BBB, CCC)'"))
+            })
+            .collect::<Result<_, _>>()?;
+        let labels: HashSet<&str> = nodes.iter().map(|(_, node)| node.label.as_str()).collect();
+        if let Some((i, _)) = nodes.iter().find(|(_, node)| {
+            !labels.contains(node.left.as_str()) || !labels.contains(node.right.as_str())
+        }) {
+            return Err(lines.error_at(*i, "a node that leads to nodes of the network"));
+        }
+        if !labels.contains("AAA") {
+            return Err(ParseError::of_whole_input("a node 'AAA'"));
+        }
+        let nodes = nodes.into_iter().map(|(_, node)| node).collect();
+        Ok(Network { nodes })
     }
 }
 
@@ -95,32 +113,75 @@ impl Network {
             .collect()
     }
 
-    fn camel_steps_until_zzz_is_reached(&self, instructions: &Instructions) -> i32 {
+    /// The steps from 'AAA' to 'ZZZ', or None if it is never reached
+    fn camel_steps_until_zzz_is_reached(&self, instructions: &Instructions) -> Option<i32> {
         let start_node = self.find_node_from_string("AAA").unwrap();
         let end_node_ends_with = "ZZZ";
 
         self.steps_until_zzz_is_reached(instructions, start_node, &end_node_ends_with)
     }
 
-    fn ghost_steps_until_zzz_is_reached(&self, instructions: &Instructions) -> u64 {
+    /// The steps until all ghosts are on a Z node at once, or None if a ghost never reaches one,
+    /// or the ghosts do not reach them in cycles
+    fn ghost_steps_until_zzz_is_reached(&self, instructions: &Instructions) -> Option<u64> {
         let start_nodes = self.find_node_from_string_end("A");
         let end_node_ends_with = "Z";
         //dbg!(&start_nodes);
         let steps_from_nodes: Vec<StepsFromNode> = start_nodes
             .iter()
             .map(|node| {
-                (
-                    *node,
-                    self.steps_until_zzz_is_reached(instructions, node, end_node_ends_with),
-                )
+                self.steps_until_zzz_is_reached(instructions, node, end_node_ends_with)
+                    .map(|steps| (*node, steps))
             })
+            .collect::<Option<_>>()?;
+        debug!("{:?}", steps_from_nodes);
+        if steps_from_nodes.len() > 1 {
+            for (node, steps) in &steps_from_nodes {
+                if !self.verify_lcm_assumption(instructions, node, *steps as usize) {
+                    return None;
+                }
+            }
+        }
+        math::lcm_of(steps_from_nodes.iter().map(|(_, steps)| *steps as u64))
+    }
+
+    /// With several ghosts, the LCM of the steps until each first reaches a Z node is only the
+    /// answer if every ghost keeps reaching a Z node every that many steps, and at no other step.
+    /// Check this by finding the cycle in its states, the node and the position in the
+    /// instructions.
+    fn verify_lcm_assumption(
+        &self,
+        instructions: &Instructions,
+        start_node: &Node,
+        steps: usize,
+    ) -> bool {
+        let nodes: HashMap<&str, &Node> =
+            self.nodes.iter().map(|n| (n.label.as_str(), n)).collect();
+        let step = |&(label, i): &(&str, usize)| {
+            let node = nodes[label];
+            let next = match instructions[i] {
+                Instruction::Left => &node.left,
+                Instruction::Right => &node.right,
+            };
+            (next.as_str(), (i + 1) % instructions.len())
+        };
+        let start = (start_node.label.as_str(), 0);
+        let cycle = cycle::find_cycle_hashed(start, step);
+        let z_steps: Vec<usize> = successors(Some(start), |state| Some(step(state)))
+            .take(cycle.prefix + cycle.period)
+            .enumerate()
+            .filter(|(_, (label, _))| label.ends_with('Z'))
+            .map(|(i, _)| i)
             .collect();
-        dbg!(&steps_from_nodes);
-        let mut least_common_multiple = 1;
-        for (_, steps) in steps_from_nodes {
-            least_common_multiple = lcm(least_common_multiple, steps as u64);
+        debug!("{}: {:?}, Z at: {:?}", start_node.label, cycle, z_steps);
+        let holds = z_steps == vec![steps] && cycle.period == steps && cycle.prefix <= steps;
+        if !holds {
+            warn!(
+                "Invalid LCM assumption for: '{}', {:?} with Z at: {:?}.",
+                start_node.label, cycle, z_steps
+            );
         }
-        least_common_multiple
+        holds
     }
 
     fn steps_until_zzz_is_reached(
@@ -128,13 +189,15 @@ impl Network {
         instructions: &Instructions,
         start_node: &Node,
         end_node_ends_with: &str,
-    ) -> i32 {
+    ) -> Option<i32> {
         let mut steps: i32 = 0;
         let mut current_node: &Node = start_node;
-        loop {
+        // There are only that many states of a node and a position in the instructions, so after
+        // as many rounds of the instructions, it is in a cycle that never reaches the end
+        for _ in 0..=self.nodes.len() {
             for instruction in instructions {
                 if current_node.label.ends_with(end_node_ends_with) {
-                    return steps;
+                    return Some(steps);
                 }
 
                 let new_node = match instruction {
@@ -146,16 +209,7 @@ impl Network {
                 steps += 1;
             }
         }
-    }
-
-    fn from_strings(input: &Vec<String>) -> Network {
-        Network {
-            nodes: input
-                .iter()
-                .filter(|line| line.len() == 16)
-                .map(|line| Node::from_string(line).unwrap())
-                .collect(),
-        }
+        None
     }
 }
 
@@ -189,19 +243,28 @@ impl Solution for Problem {
         )
     }
 
-    fn solve_part_one(&self, input: Input, _is_example: bool) -> Answer {
-        let instructions = Instructions::parse(input.clone());
-        let network = Network::parse(input);
-        let camel_steps_until_zzz_is_reached =
-            network.camel_steps_until_zzz_is_reached(&instructions);
-        camel_steps_until_zzz_is_reached as Answer
+    fn parse_input(&self, input: Input) -> Result<(), ParseError> {
+        Instructions::parse(input.clone())?;
+        Network::parse(input).map(|_| ())
+    }
+
+    fn solve_part_one(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let instructions = Instructions::parse(input.clone())?;
+        let network = Network::parse(input)?;
+        let camel_steps_until_zzz_is_reached = network
+            .camel_steps_until_zzz_is_reached(&instructions)
+            .ok_or_else(|| ParseError::of_whole_input("a path from 'AAA' to 'ZZZ'"))?;
+        Ok(camel_steps_until_zzz_is_reached as Answer)
     }
 
-    fn solve_part_two(&self, input: Input, _is_example: bool) -> Answer {
-        let instructions = Instructions::parse(input.clone());
-        let network = Network::parse(input);
-        let ghost_steps_until_zzz_is_reached =
-            network.ghost_steps_until_zzz_is_reached(&instructions);
-        ghost_steps_until_zzz_is_reached as Answer
+    fn solve_part_two(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let instructions = Instructions::parse(input.clone())?;
+        let network = Network::parse(input)?;
+        let ghost_steps_until_zzz_is_reached = network
+            .ghost_steps_until_zzz_is_reached(&instructions)
+            .ok_or_else(|| {
+                ParseError::of_whole_input("paths from nodes ending in 'A' that cycle through 'Z'")
+            })?;
+        Ok(ghost_steps_until_zzz_is_reached as Answer)
     }
 }
@@ -1,74 +1,36 @@
-use std::collections::VecDeque;
-
 use crate::*;
 
 type Int = i32;
-type Sequence = VecDeque<Int>;
+
+/// The most values a History can have, so that its differences fit in an i128
+const MAX_VALUES: usize = 64;
 
 struct History {
-    sequences: Vec<Sequence>,
+    values: Vec<Int>,
 }
 
 impl History {
-    fn from_string(sequence: &str) -> History {
-        let history: Sequence = sequence
+    fn from_string(sequence: &str) -> Option<History> {
+        let values = sequence
             .split_ascii_whitespace()
-            .map(|s| s.parse::<Int>().unwrap())
-            .collect();
-        History {
-            sequences: vec![history],
-        }
-    }
-
-    fn find_differences(&mut self) {
-        loop {
-            let last_sequence = self.sequences.last().unwrap();
-            if last_sequence.iter().all(|x| *x == 0) {
-                break;
-            }
-            let differences = History::get_differences(last_sequence);
-            self.sequences.push(differences);
-        }
+            .map(|s| s.parse::<Int>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        (values.len() <= MAX_VALUES).then_some(History { values })
     }
 
-    fn extrapolate(&mut self) {
-        let n = self.sequences.len() - 1;
-        for i in (0..n).rev() {
-            if i == n {
-                self.sequences[i].push_back(0);
-            } else {
-                let extrapolation =
-                    self.sequences[i + 1].back().unwrap() + self.sequences[i].back().unwrap();
-                self.sequences[i].push_back(extrapolation);
-            }
-        }
+    fn next_value(&self) -> i128 {
+        polynomial::extrapolate(&self.values)
     }
 
-    fn extrapolate_first_value(&mut self) {
-        let n = self.sequences.len() - 1;
-        for i in (0..n).rev() {
-            if i == n {
-                self.sequences[i].push_front(0);
-            } else {
-                let extrapolation =
-                    self.sequences[i].front().unwrap() - self.sequences[i + 1].front().unwrap();
-                self.sequences[i].push_front(extrapolation);
-            }
-        }
-    }
-
-    fn get_differences(input_sequence: &Sequence) -> Sequence {
-        Vec::from(input_sequence.clone())
-            .windows(2)
-            .map(|pair| pair[1] - pair[0])
-            .collect()
+    fn previous_value(&self) -> i128 {
+        polynomial::extrapolate_backward(&self.values)
     }
 }
 
 impl std::fmt::Debug for History {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut extrapolation = String::new();
-        for (i, line) in self.sequences.iter().enumerate() {
+        for (i, line) in polynomial::differences(&self.values).iter().enumerate() {
             for _ in 0..i {
                 extrapolation += "  ";
             }
@@ -86,41 +48,45 @@ struct OASIS {
     histories: Vec<History>,
 }
 
-impl OASIS {
-    fn parse(input: Input) -> OASIS {
-        OASIS::from_strings(&input.lines().map(|s| s.to_string()).collect())
+impl Parse for OASIS {
+    fn parse(input: Input) -> Result<Self, ParseError> {
+        let lines = InputLines::from(input);
+        let histories = lines
+            .lines()
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| !s.is_empty())
+            .map(|(i, s)| {
+                History::from_string(s)
+                    .ok_or_else(|| lines.error_at(i, "at most 64 numbers that fit in 32 bits"))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(OASIS { histories })
     }
+}
 
-    fn from_strings(sequences: &Vec<String>) -> OASIS {
-        let histories: Vec<History> = sequences
+impl OASIS {
+    /// The sum of `value` of each History, or None if it does not fit in an Answer
+    fn sum_of_histories(&self, value: fn(&History) -> i128) -> Option<Answer> {
+        let sum = self
+            .histories
             .iter()
-            .filter(|s| !s.is_empty())
-            .map(|s| History::from_string(s))
-            .collect();
-        OASIS { histories }
+            .try_fold(0, |sum: i128, history| sum.checked_add(value(history)))?;
+        Answer::try_from(sum).ok()
     }
 
-    fn extrapolate_histories(&mut self) {
-        self.histories.iter_mut().for_each(|history| {
-            history.find_differences();
-            history.extrapolate();
-            history.extrapolate_first_value()
-        });
+    fn sum_of_histories_next_values(&self) -> Option<Answer> {
+        self.sum_of_histories(History::next_value)
     }
 
-    fn sum_of_histories_last_values(&self) -> Int {
-        self.histories
-            .iter()
-            .map(|h| h.sequences.first().unwrap().back().unwrap())
-            .sum()
+    fn sum_of_histories_previous_values(&self) -> Option<Answer> {
+        self.sum_of_histories(History::previous_value)
     }
+}
 
-    fn sum_of_histories_first_values(&self) -> Int {
-        self.histories
-            .iter()
-            .map(|h| h.sequences.first().unwrap().front().unwrap())
-            .sum()
-    }
+/// The error for histories whose extrapolated values do not add up to an Answer
+fn overflow() -> ParseError {
+    ParseError::of_whole_input("extrapolated values whose sum fits in 64 bits")
 }
 
 #[derive(Default)]
@@ -151,17 +117,19 @@ impl Solution for Problem {
         )
     }
 
-    fn solve_part_one(&self, input: Input, _is_example: bool) -> Answer {
-        let mut oasis = OASIS::parse(input);
-        oasis.extrapolate_histories();
-        let sum_of_histories_last_values = oasis.sum_of_histories_last_values();
-        sum_of_histories_last_values as Answer
+    fn parse_input(&self, input: Input) -> Result<(), ParseError> {
+        OASIS::parse(input).map(|_| ())
+    }
+
+    fn solve_part_one(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let oasis = OASIS::parse(input)?;
+        oasis.sum_of_histories_next_values().ok_or_else(overflow)
     }
 
-    fn solve_part_two(&self, input: Input, _is_example: bool) -> Answer {
-        let mut oasis = OASIS::parse(input);
-        oasis.extrapolate_histories();
-        let sum_of_histories_first_values = oasis.sum_of_histories_first_values();
-        sum_of_histories_first_values as Answer
+    fn solve_part_two(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let oasis = OASIS::parse(input)?;
+        oasis
+            .sum_of_histories_previous_values()
+            .ok_or_else(overflow)
     }
 }
@@ -1,53 +1,31 @@
 use core::panic;
 use std::cmp::{max, min};
+use std::collections::HashSet;
 
+use crate::geometry;
+use crate::grid::{Direction, Point};
 use crate::*;
 
-static PRINT_DISTANCES: bool = false;
-
 type Int = i32;
 
-#[derive(Debug)]
-enum Direction {
-    North,
-    East,
-    South,
-    West,
-}
-
-#[derive(Debug, PartialEq, Clone)]
-enum Tile {
-    NorthSouthPipe,
-    EastWestPipe,
-    NorthEastPipe,
-    NorthWestPipe,
-    SouthWestPipe,
-    SouthEastPipe,
-    Ground,
-    AnimalStartingPosition,
+define_convertable_enum! {
+    Tile {
+        NorthSouthPipe => '|',
+        EastWestPipe => '-',
+        NorthEastPipe => 'L',
+        NorthWestPipe => 'J',
+        SouthWestPipe => '7',
+        SouthEastPipe => 'F',
+        Ground => '.' | 'O' | 'I',
+        AnimalStartingPosition => 'S',
+    }
 }
 
 impl Tile {
-    fn from_char(c: char) -> Tile {
-        use Tile::*;
-        match c {
-            '|' => NorthSouthPipe,
-            '-' => EastWestPipe,
-            'L' => NorthEastPipe,
-            'J' => NorthWestPipe,
-            '7' => SouthWestPipe,
-            'F' => SouthEastPipe,
-            '.' => Ground,
-            'S' => AnimalStartingPosition,
-            'O' | 'I' => Ground,
-            _ => panic!("Invalid Tile: '{}'.", c),
-        }
-    }
-
     fn goes(&self, direction: Direction) -> bool {
         use Direction::*;
         use Tile::*;
-        let tile = self.clone();
+        let tile = *self;
         return match direction {
             North => match tile {
                 NorthSouthPipe | NorthEastPipe | NorthWestPipe | AnimalStartingPosition => true,
@@ -69,15 +47,7 @@ impl Tile {
     }
 
     fn connected(&self, other: &Tile, direction_of_other: Direction) -> bool {
-        use Direction::*;
-        let from: Tile = self.clone();
-        let to: Tile = other.clone();
-        match direction_of_other {
-            North => from.goes(North) && to.goes(South),
-            East => from.goes(East) && to.goes(West),
-            South => from.goes(South) && to.goes(North),
-            West => from.goes(West) && to.goes(East),
-        }
+        self.goes(direction_of_other) && other.goes(direction_of_other.opposite())
     }
 }
 
@@ -96,34 +66,44 @@ struct Maze {
     columns: usize,
 }
 
-impl Maze {
-    fn parse(input: Input) -> Maze {
-        Maze::from_strings(&input.lines().map(|s| s.to_string()).collect())
+impl Parse for Maze {
+    fn parse(input: Input) -> Result<Maze, ParseError> {
+        let starts = input
+            .matches(Tile::AnimalStartingPosition.to_char())
+            .count();
+        if starts != 1 {
+            return Err(ParseError::of_whole_input(
+                "a single animal starting position",
+            ));
+        }
+        let maze = Maze::from_strings(&input.lines().map(|s| s.to_string()).collect::<Vec<_>>())?;
+        maze.check_loop()?;
+        Ok(maze)
     }
+}
 
-    fn from_strings(tiles: &Vec<String>) -> Maze {
-        let maze: Vec<Tiles> = tiles
-            .iter()
-            .filter(|s| !s.is_empty())
-            .map(|s| s.chars().map(|c| Tile::from_char(c)).collect())
-            .collect();
+impl Maze {
+    fn from_strings(tiles: &[String]) -> Result<Maze, ParseError> {
+        let lines = InputLines::from(tiles.join("\n")).filter_empty_lines();
+        lines.check_rectangular()?;
+        let maze: Vec<Tiles> = lines.try_into_grid()?;
         let rows = maze.len();
-        let columns = maze.first().unwrap().len();
+        let columns = maze.first().map_or(0, Vec::len);
         let distances: Distances = vec![vec![-1; columns]; rows];
 
-        Maze {
-            to_strings: tiles.clone(),
+        Ok(Maze {
+            to_strings: tiles.to_vec(),
             maze,
             distances,
             visited: Vec::new(),
             rows,
             columns,
-        }
+        })
     }
 
     fn get_animal_starting_position(&self) -> Option<Position> {
-        for i in 0..(self.columns - 1) {
-            for j in 0..(self.rows - 1) {
+        for i in 0..self.columns {
+            for j in 0..self.rows {
                 let tile: &Tile = &self.maze[j][i];
                 if *tile == Tile::AnimalStartingPosition {
                     return Some((i as Int, j as Int));
@@ -133,32 +113,47 @@ impl Maze {
         None
     }
 
-    fn move_north(tile: Position) -> Position {
-        (tile.0, tile.1 - 1)
-    }
-
-    fn move_east(tile: Position) -> Position {
-        (tile.0 + 1, tile.1)
-    }
-
-    fn move_south(tile: Position) -> Position {
-        (tile.0, tile.1 + 1)
+    /// Check that exactly two pipes connect to the animal starting position, and that following
+    /// them leads back to it, so walking the loop cannot get stuck
+    fn check_loop(&self) -> Result<(), ParseError> {
+        let error = || ParseError::of_whole_input("a loop of pipes through the animal");
+        let start = self.get_animal_starting_position().ok_or_else(error)?;
+        let exits: Vec<Direction> = Direction::iter()
+            .filter(|&direction| self.connects(start, direction))
+            .collect();
+        if exits.len() != 2 {
+            return Err(error());
+        }
+        let (mut position, mut direction) = (start, exits[0]);
+        // A pipe only has two ends, so the loop visits every tile at most once
+        for _ in 0..self.rows * self.columns {
+            position = self.get_neighbour(position, direction).0 .1;
+            if position == start {
+                return Ok(());
+            }
+            let tile = self.get_tile(position).unwrap();
+            direction = Direction::iter()
+                .find(|&next| next != direction.opposite() && tile.goes(next))
+                .unwrap();
+            if !self.connects(position, direction) {
+                return Err(error());
+            }
+        }
+        Err(error())
     }
 
-    fn move_west(tile: Position) -> Position {
-        (tile.0 - 1, tile.1)
+    /// Whether the tile at `position` and its neighbour in `direction` are connected
+    fn connects(&self, position: Position, direction: Direction) -> bool {
+        let ((neighbour, _), _) = self.get_neighbour(position, direction);
+        match (self.get_tile(position), neighbour) {
+            (Some(tile), Some(neighbour)) => tile.connected(&neighbour, direction),
+            _ => false,
+        }
     }
 
     fn get_neighbour(&self, tile: Position, direction: Direction) -> Neighbour {
-        use Direction::*;
-
-        let new_position = match direction {
-            North => Maze::move_north(tile),
-            East => Maze::move_east(tile),
-            South => Maze::move_south(tile),
-            West => Maze::move_west(tile),
-        };
-
+        let unit = direction.to_point();
+        let new_position = (tile.0 + unit.x as Int, tile.1 + unit.y as Int);
         (self.get_tile_and_position(new_position), direction)
     }
 
@@ -170,7 +165,7 @@ impl Maze {
         {
             return None;
         }
-        Some(self.maze[position.1 as usize][position.0 as usize].clone())
+        Some(self.maze[position.1 as usize][position.0 as usize])
     }
 
     fn get_tile_and_position(&self, position: Position) -> TileAndPosition {
@@ -189,71 +184,40 @@ impl Maze {
         new_distance
     }
 
-    fn get_area_enclosed_by_visited_tiles(&self) -> f64 {
-        // We use the Shoelace formule to find this area
-        // See: https://en.wikipedia.org/wiki/Shoelace_formula
-
-        let circular_visited: Vec<Position> = self
+    /// The number of tiles enclosed by the loop, or `None` if it overflows
+    fn get_interior_points(&self) -> Option<Int> {
+        let vertices: Vec<Point> = self
             .visited
             .iter()
-            .chain(vec![self.visited.first().unwrap()])
-            .cloned()
+            .map(|&(x, y)| Point::new(x.into(), y.into()))
             .collect();
-        let area: f64 = circular_visited
-            .windows(2)
-            .map(|v| {
-                if let [a, b] = v {
-                    (a.0 * b.1 - b.0 * a.1) as f64
-                } else {
-                    panic!("This should never happen")
-                }
-            })
-            .sum::<f64>()
-            / 2.0;
-        area.abs()
-    }
-
-    fn get_interior_points(&self) -> Int {
-        // Use Pick's theorem to find the interior points from the area and boundary points
-        // See: https://en.wikipedia.org/wiki/Pick%27s_theorem
-        // Let i be the number of integer points interior to the polygon
-        // Let b be the number of integer points on its boundary
-        // Then the area A of this polygon is: A = i + b/2 - 1
-        // So then i = A - b/2 + 1
-
-        let area = self.get_area_enclosed_by_visited_tiles();
-        let boundary_points = self.visited.len();
-        let interior_points = area - (boundary_points as f64 / 2.0) + 1.0;
-        interior_points as Int
+        geometry::interior_points(&vertices).and_then(|n| Int::try_from(n).ok())
     }
 
     fn print_visit_distance_to_start(position: &Position, tile: &Tile, distance: i32) {
-        if PRINT_DISTANCES {
-            println!(
-                "[{}, {}]: {:?} (distance: {})",
-                position.0, position.1, tile, distance
-            );
-        }
+        trace!(
+            "[{}, {}]: {:?} (distance: {})",
+            position.0,
+            position.1,
+            tile,
+            distance
+        );
     }
 
     fn find_longest_distance_from_animal_starting_position(&mut self) -> Int {
-        use Direction::*;
         let mut current: TileAndPosition =
             self.get_tile_and_position(self.get_animal_starting_position().unwrap());
         let mut distance = 0;
 
         loop {
-            let (tile, position) = (current.0.clone().unwrap(), current.1);
+            let (tile, position) = (current.0.unwrap(), current.1);
             let mut travelled = false;
             self.update_distance(distance, position);
             Maze::print_visit_distance_to_start(&position, &tile, distance);
 
-            let neighbours: Vec<Neighbour> = vec![
-                self.get_neighbour(position, North),
-                self.get_neighbour(position, East),
-                self.get_neighbour(position, South),
-                self.get_neighbour(position, West),
-            ];
+            let neighbours: Vec<Neighbour> = Direction::iter()
+                .map(|direction| self.get_neighbour(position, direction))
+                .collect();
 
             for ((other_tile_option, other_position), direction) in neighbours {
                 if let Some(other_tile) = other_tile_option {
@@ -268,7 +232,7 @@ impl Maze {
                         self.visited.push(current.1);
                         distance += 1;
                         travelled = true;
-                        let tile = current.0.clone().unwrap();
+                        let tile = current.0.unwrap();
                         if tile == Tile::AnimalStartingPosition {
                             Maze::print_visit_distance_to_start(&position, &tile, distance);
                             // Now, traverse the visited tiles in reverse, and update the Distances
@@ -322,6 +286,55 @@ impl std::fmt::Debug for Maze {
     }
 }
 
+/// A random loop around a spanning tree of `size` by `size` cells, scaled up to 3 by 3 tiles per
+/// cell. Every cell starts as a loop around its middle tile, and the loops of two cells connected
+/// by the tree are merged into one, until there is a single loop that encloses all middle tiles.
+fn generate_loop(rng: &mut Rng, size: usize) -> HashSet<(Position, Position)> {
+    let cells = (size * size * 3 / 4).max(1);
+    let start = (rng.below(size) as Int, rng.below(size) as Int);
+    let mut tree = vec![start];
+    let mut connections = Vec::new();
+    while tree.len() < cells {
+        let (x, y) = *rng.choose(&tree);
+        let (dx, dy) = *rng.choose(&[(1, 0), (-1, 0), (0, 1), (0, -1)]);
+        let next = (x + dx, y + dy);
+        let within = |v: Int| v >= 0 && v < size as Int;
+        if within(next.0) && within(next.1) && !tree.contains(&next) {
+            tree.push(next);
+            connections.push(((x, y).min(next), (x, y).max(next)));
+        }
+    }
+    let mut edges: HashSet<(Position, Position)> = HashSet::new();
+    for &(x, y) in &tree {
+        let (x, y) = (3 * x, 3 * y);
+        for i in 0..2 {
+            edges.insert(((x + i, y), (x + i + 1, y)));
+            edges.insert(((x + i, y + 2), (x + i + 1, y + 2)));
+            edges.insert(((x, y + i), (x, y + i + 1)));
+            edges.insert(((x + 2, y + i), (x + 2, y + i + 1)));
+        }
+    }
+    for ((x, y), (next_x, _)) in connections {
+        let (x, y) = (3 * x, 3 * y);
+        let (removed, added) = if next_x > x / 3 {
+            (
+                [((x + 2, y), (x + 2, y + 1)), ((x + 3, y), (x + 3, y + 1))],
+                [((x + 2, y), (x + 3, y)), ((x + 2, y + 1), (x + 3, y + 1))],
+            )
+        } else {
+            (
+                [((x, y + 2), (x + 1, y + 2)), ((x, y + 3), (x + 1, y + 3))],
+                [((x, y + 2), (x, y + 3)), ((x + 1, y + 2), (x + 1, y + 3))],
+            )
+        };
+        removed.iter().for_each(|edge| {
+            edges.remove(edge);
+        });
+        edges.extend(added);
+    }
+    edges
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -329,7 +342,9 @@ mod tests {
     impl Maze {
         // Test case for Example Part 1
         fn test_distance(input: Vec<&str>, expected_distance: Int) {
-            let mut maze = Maze::from_strings(&input.iter().map(|s| s.to_string()).collect());
+            let mut maze =
+                Maze::from_strings(&input.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+                    .unwrap();
             let distance = maze.find_longest_distance_from_animal_starting_position();
             dbg!(maze.to_strings);
             test!(expected_distance, distance);
@@ -337,11 +352,13 @@ mod tests {
 
         // Test case for Example Part 2
         fn test_interior_points(input: Vec<&str>, expected_interior_points: Int) {
-            let mut maze = Maze::from_strings(&input.iter().map(|s| s.to_string()).collect());
+            let mut maze =
+                Maze::from_strings(&input.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+                    .unwrap();
             let _ = maze.find_longest_distance_from_animal_starting_position();
             let interior_points = maze.get_interior_points();
             dbg!(maze.to_strings);
-            test!(expected_interior_points, interior_points);
+            test!(Some(expected_interior_points), interior_points);
         }
     }
 
@@ -459,16 +476,73 @@ impl Solution for Problem {
         563
     }
 
-    fn solve_part_one(&self, input: Input, _is_example: bool) -> Answer {
-        let mut maze = Maze::parse(input);
+    /// A pipe loop around a spanning tree of about `size` by `size` cells, with other pipes
+    /// around it, and a margin so the start is never in the last row or column
+    fn generate_input(&self, rng: &mut Rng, size: usize) -> Option<Input> {
+        let edges = generate_loop(rng, size.max(1));
+        let tiles = 3 * size.max(1) + 2;
+        let mut grid: Vec<Vec<char>> = (0..tiles)
+            .map(|_| {
+                (0..tiles)
+                    .map(|_| *rng.choose(&['|', '-', 'L', 'J', '7', 'F', '.']))
+                    .collect()
+            })
+            .collect();
+        let mut pipes: HashMap<Position, Vec<Direction>> = HashMap::new();
+        for &(a, b) in &edges {
+            let (a_to_b, b_to_a) = if a.0 < b.0 {
+                (Direction::East, Direction::West)
+            } else {
+                (Direction::South, Direction::North)
+            };
+            pipes.entry(a).or_default().push(a_to_b);
+            pipes.entry(b).or_default().push(b_to_a);
+        }
+        for (&(x, y), directions) in &pipes {
+            let tile = [
+                Tile::NorthSouthPipe,
+                Tile::EastWestPipe,
+                Tile::NorthEastPipe,
+                Tile::NorthWestPipe,
+                Tile::SouthWestPipe,
+                Tile::SouthEastPipe,
+            ]
+            .into_iter()
+            .find(|tile| directions.iter().all(|&d| tile.goes(d)))
+            .unwrap();
+            grid[y as usize + 1][x as usize + 1] = tile.to_char();
+        }
+        // Only the two pipes of the loop may connect to the start
+        let mut positions: Vec<Position> = pipes.keys().copied().collect();
+        positions.sort();
+        let (x, y) = *rng.choose(&positions);
+        let (x, y) = (x as usize + 1, y as usize + 1);
+        grid[y][x] = Tile::AnimalStartingPosition.to_char();
+        for (neighbour_x, neighbour_y) in [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)] {
+            let neighbour = (neighbour_x as Int - 1, neighbour_y as Int - 1);
+            if !pipes.contains_key(&neighbour) {
+                grid[neighbour_y][neighbour_x] = Tile::Ground.to_char();
+            }
+        }
+        let lines: Vec<String> = grid.into_iter().map(String::from_iter).collect();
+        Some(lines.join("\n") + "\n")
+    }
+
+    fn parse_input(&self, input: Input) -> Result<(), ParseError> {
+        Maze::parse(input).map(|_| ())
+    }
+
+    fn solve_part_one(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let mut maze = Maze::parse(input)?;
         let distance = maze.find_longest_distance_from_animal_starting_position();
-        distance as Answer
+        Ok(distance as Answer)
     }
 
-    fn solve_part_two(&self, input: Input, _is_example: bool) -> Answer {
-        let mut maze = Maze::parse(input);
+    fn solve_part_two(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let mut maze = Maze::parse(input)?;
         maze.find_longest_distance_from_animal_starting_position();
-        let interior_points = maze.get_interior_points();
-        interior_points as Answer
+        maze.get_interior_points()
+            .map(|n| n as Answer)
+            .ok_or_else(|| ParseError::of_whole_input("a maze whose area fits in 64 bits"))
     }
 }
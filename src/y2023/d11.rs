@@ -24,13 +24,20 @@ impl std::fmt::Debug for Data {
     }
 }
 
-impl Data {
-    fn from_char(c: char) -> Data {
+impl CharConvertable for Data {
+    fn try_from_char(c: char) -> Option<Data> {
         use Data::*;
         match c {
-            '.' => EmptySpace,
-            '#' => Galaxy(None),
-            _ => panic!("Not valid Data: '{}'.", c),
+            '.' => Some(EmptySpace),
+            '#' => Some(Galaxy(None)),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Data::EmptySpace => '.',
+            Data::Galaxy(_) => '#',
         }
     }
 }
@@ -58,13 +65,14 @@ struct Image {
 }
 
 impl Image {
-    fn parse(input: Input) -> Image {
-        let input: Vec<String> = input
-            .lines()
-            .filter(|s| !s.is_empty())
-            .map(|s| s.to_string())
-            .collect();
-        Image::from_strings(&input)
+    fn parse(input: Input) -> Result<Image, ParseError> {
+        let lines = InputLines::from(input.clone()).filter_empty_lines();
+        if lines.is_empty() {
+            return Err(ParseError::at(&input, "", "an image"));
+        }
+        lines.check_rectangular()?;
+        let strings = lines.lines().to_vec();
+        Ok(Image::from_grid(strings, lines.try_into_grid()?))
     }
 
     fn from_strings(input: &Vec<String>) -> Image {
@@ -73,10 +81,14 @@ impl Image {
             .filter(|s| !s.is_empty())
             .map(|s| s.chars().map(|c| Data::from_char(c)).collect())
             .collect();
+        Image::from_grid(input.clone(), data)
+    }
+
+    fn from_grid(to_strings: Vec<String>, data: Grid<Data>) -> Image {
         let rows = data.len() as Int;
         let columns = data.first().unwrap().len() as Int;
         let mut image = Image {
-            to_strings: input.clone(),
+            to_strings,
             data,
             rows,
             columns,
@@ -353,35 +365,33 @@ impl Solution for Problem {
     }
 
     define_examples! {
-        (
-            "
-            ...#......
-            .......#..
-            #.........
-            ..........
-            ......#...
-            .#........
-            .........#
-            ..........
-            .......#..
-            #...#.....
-            ",
-            Expect::PartsOneAndTwo(374, 8410),
-        )
+        (file("examples/2023/11_a.txt"), Expect::PartOne(374),),
+        (file("examples/2023/11_a.txt"), Expect::PartTwo(1030), expansion = 10,),
+        (file("examples/2023/11_a.txt"), Expect::PartTwo(8410), expansion = 100,),
+    }
+
+    /// An image of `size` by `size`, in which about one in ten cells is a galaxy
+    fn generate_input(&self, rng: &mut Rng, size: usize) -> Option<Input> {
+        let size = size.max(2);
+        Some(rng.grid(size, size, |rng| if rng.chance(0.1) { '#' } else { '.' }))
+    }
+
+    fn parse_input(&self, input: Input) -> Result<(), ParseError> {
+        Image::parse(input).map(|_| ())
     }
 
-    fn solve_part_one(&self, input: Input, _is_example: bool) -> Answer {
-        let image = Image::parse(input);
+    fn solve_part_one(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let image = Image::parse(input)?;
         let expanded_image = image.expand_universe();
         let sum_of_distances = expanded_image.compute_sum_of_distances_between_all_galaxies();
-        sum_of_distances
+        Ok(sum_of_distances)
     }
 
-    fn solve_part_two(&self, input: Input, is_example: bool) -> Answer {
-        let image = Image::parse(input);
-        let scale = if is_example { 100 } else { 1_000_000 };
+    fn solve_part_two(&self, input: Input, context: &Context) -> Result<Answer, ParseError> {
+        let image = Image::parse(input)?;
+        let scale = context.get_or("expansion", 1_000_000);
         let scaled_image = image.expand_universe_with_factor(scale);
         let sum_of_distances = scaled_image.compute_sum_of_distances_between_all_galaxies();
-        sum_of_distances
+        Ok(sum_of_distances)
     }
 }
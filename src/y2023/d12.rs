@@ -3,25 +3,28 @@ use crate::*;
 type Key = (String, Vec<Int>);
 type Memo = HashMap<Key, Int>;
 
+#[derive(Debug)]
 struct Record {
     springs: String,
     damaged_spring_groups: Vec<Int>,
 }
 
 impl Record {
-    fn from_string(input: &str) -> Record {
+    fn from_string(input: &str) -> Option<Record> {
         let mut iter = input.split_ascii_whitespace();
-        let springs = iter.next().unwrap().to_string();
+        let springs = iter.next()?.to_string();
+        if springs.contains(|c| !".#?".contains(c)) {
+            return None;
+        }
         let damaged_spring_groups: Vec<Int> = iter
-            .next()
-            .unwrap()
+            .next()?
             .split(",")
-            .map(|v| v.parse::<Int>().unwrap())
-            .collect();
-        Record {
+            .map(|v| v.parse::<Int>().ok())
+            .collect::<Option<_>>()?;
+        Some(Record {
             springs,
             damaged_spring_groups,
-        }
+        })
     }
 
     fn solve(&self, memo: &mut Memo) -> Int {
@@ -46,7 +49,7 @@ impl Record {
 
     #[cfg(test)]
     fn test(input: &str, expected_arrangements: Int, memo: &mut Memo) {
-        let record = Record::from_string(input);
+        let record = Record::from_string(input).unwrap();
         let actual_arrangements = record.solve(memo);
         test!(
             expected_arrangements,
@@ -59,7 +62,7 @@ impl Record {
 
     #[cfg(test)]
     fn test_expanded(input: &str, expected_arrangements: Int, memo: &mut Memo) {
-        let record = Record::from_string(input).expand();
+        let record = Record::from_string(input).unwrap().expand();
         let actual_arrangements = record.solve(memo);
         test!(
             expected_arrangements,
@@ -71,6 +74,24 @@ impl Record {
     }
 }
 
+type Records = Vec<Record>;
+
+impl Parse for Records {
+    fn parse(input: Input) -> Result<Self, ParseError> {
+        let lines = InputLines::from(input);
+        lines
+            .lines()
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| !s.is_empty())
+            .map(|(i, s)| {
+                Record::from_string(s)
+                    .ok_or_else(|| lines.error_at(i, "springs of '.', '#' or '?' and groups"))
+            })
+            .collect()
+    }
+}
+
 /// # Brief
 /// Take in a record and a vector of groups of damaged strings, and return the amount of
 /// different valid records that are possible.
@@ -108,6 +129,11 @@ fn solve(record: String, groups: Vec<Int>, memo: &mut Memo) -> Int {
                     }
                 }
                 '#' => {
+                    // The damaged springs from the start are already too many for the group
+                    if i as Int >= *groups.first().unwrap() {
+                        result = 0;
+                        break;
+                    }
                     continue;
                 }
                 '?' => {
@@ -127,6 +153,8 @@ fn solve(record: String, groups: Vec<Int>, memo: &mut Memo) -> Int {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
     #[test]
     fn test_part_one_example() {
         let mut memo = Memo::new();
@@ -148,6 +176,42 @@ mod tests {
         Record::test_expanded("????.######..#####. 1,6,5", 2500, &mut memo);
         Record::test_expanded("?###???????? 3,2,1", 506250, &mut memo);
     }
+
+    /// Records of up to 12 springs, with up to 4 damaged spring groups
+    fn records() -> impl Strategy<Value = Record> {
+        ("[.#?]{1,12}", prop::collection::vec(1..4 as Int, 0..4)).prop_map(
+            |(springs, damaged_spring_groups)| Record {
+                springs,
+                damaged_spring_groups,
+            },
+        )
+    }
+
+    /// Count the arrangements by trying every way to replace the `?`s
+    fn count_exhaustively(record: &Record) -> Int {
+        let unknown: Vec<usize> = record.springs.match_indices('?').map(|(i, _)| i).collect();
+        (0..1 << unknown.len())
+            .filter(|damaged| {
+                let mut springs: Vec<char> = record.springs.chars().collect();
+                for (bit, &i) in unknown.iter().enumerate() {
+                    springs[i] = if damaged & (1 << bit) != 0 { '#' } else { '.' };
+                }
+                let groups: Vec<Int> = springs
+                    .split(|&c| c == '.')
+                    .filter(|group| !group.is_empty())
+                    .map(|group| group.len() as Int)
+                    .collect();
+                groups == record.damaged_spring_groups
+            })
+            .count() as Int
+    }
+
+    proptest! {
+        #[test]
+        fn solve_matches_exhaustive_search(record in records()) {
+            prop_assert_eq!(count_exhaustively(&record), record.solve(&mut Memo::new()));
+        }
+    }
 }
 
 #[derive(Default)]
@@ -181,23 +245,48 @@ impl Solution for Problem {
         )
     }
 
-    fn solve_part_one(&self, input: Input, _is_example: bool) -> Answer {
+    /// `size` records of up to 20 springs, of which about two in five are unknown
+    fn generate_input(&self, rng: &mut Rng, size: usize) -> Option<Input> {
+        let mut input = String::new();
+        for _ in 0..size.max(1) {
+            let length = rng.range(1, 20) as usize;
+            let mut springs: Vec<char> = (0..length)
+                .map(|_| if rng.chance(0.5) { '#' } else { '.' })
+                .collect();
+            springs[rng.below(length)] = '#';
+            let groups: Vec<String> = springs
+                .split(|&c| c == '.')
+                .filter(|group| !group.is_empty())
+                .map(|group| group.len().to_string())
+                .collect();
+            let springs: String = springs
+                .into_iter()
+                .map(|c| if rng.chance(0.4) { '?' } else { c })
+                .collect();
+            input += &format!("{} {}\n", springs, groups.join(","));
+        }
+        Some(input)
+    }
+
+    fn parse_input(&self, input: Input) -> Result<(), ParseError> {
+        Records::parse(input).map(|_| ())
+    }
+
+    fn solve_part_one(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
         let mut memo = Memo::new();
-        let sum: Int = input
-            .lines()
-            .filter(|s| !s.is_empty())
-            .map(|s| Record::from_string(s).solve(&mut memo))
+        let sum: Int = Records::parse(input)?
+            .iter()
+            .map(|record| record.solve(&mut memo))
             .sum();
-        sum
+        Ok(sum)
     }
 
-    fn solve_part_two(&self, input: Input, _is_example: bool) -> Answer {
+    fn solve_part_two(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
         let mut memo = Memo::new();
-        let sum: Int = input
-            .lines()
-            .filter(|s| !s.is_empty())
-            .map(|s| Record::from_string(s).expand().solve(&mut memo))
+        let sum: Int = Records::parse(input)?
+            .iter()
+            .map(|record| record.expand().solve(&mut memo))
             .sum();
-        sum
+        Ok(sum)
     }
 }
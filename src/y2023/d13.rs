@@ -1,9 +1,8 @@
 use colored::Colorize;
 
-use crate::{define_examples, solution::*};
+use crate::{define_examples, grid::Map, solution::*};
 
 type Int = i32;
-type Grid<T> = Vec<Vec<T>>;
 
 #[derive(Debug, Clone)]
 enum Line {
@@ -23,9 +22,7 @@ impl Line {
 
 #[derive(Clone)]
 struct Pattern {
-    grid: Grid<char>,
-    rows: usize,
-    columns: usize,
+    grid: Map<char>,
     smudges: Int,
     summary: Option<Int>,
     reflection: Option<Line>,
@@ -36,9 +33,9 @@ impl Debug for Pattern {
         let mut grid_string: String = String::new();
         let reflection = self.reflection.as_ref().unwrap();
         let summary = self.summary.as_ref().unwrap();
-        for i in 0..self.rows {
+        for i in 0..self.grid.get_rows() {
             grid_string.push_str("  ");
-            for j in 0..self.columns {
+            for j in 0..self.grid.get_columns() {
                 let element = if reflection.in_line(i as Int, j as Int)
                     || reflection.in_line(i as Int - 1, j as Int - 1)
                 {
@@ -55,125 +52,68 @@ impl Debug for Pattern {
         write!(
             f,
             "\nPattern({}x{}): (smudges: {}, summary: {})\n{}",
-            self.rows, self.columns, self.smudges, summary, grid_string
+            self.grid.get_rows(),
+            self.grid.get_columns(),
+            self.smudges,
+            summary,
+            grid_string
         )
     }
 }
 
 impl Pattern {
-    fn parse(input: Input, smudges: Int) -> Vec<Pattern> {
-        Pattern::parse_patterns(&input.lines().map(|s| s.to_string()).collect(), smudges)
+    /// Parse Patterns separated by empty lines
+    fn parse(input: Input, smudges: Int) -> Result<Vec<Pattern>, ParseError> {
+        InputLines::from(input)
+            .paragraphs()
+            .into_iter()
+            .map(|paragraph| Pattern::parse_pattern(paragraph, smudges))
+            .collect()
     }
 
-    /// Parse a single Pattern
-    fn parse_pattern(input: Vec<String>, smudges: Int) -> Pattern {
-        let rows = input.len();
-        let columns = input.first().unwrap().len();
-        let grid: Grid<char> = input.iter().map(|s| s.chars().collect()).collect();
+    /// Parse a single Pattern, which must have a line of reflection
+    fn parse_pattern(input: InputLines, smudges: Int) -> Result<Pattern, ParseError> {
+        let grid: Map<char> = input.clone().try_into_map()?;
         let mut result = Pattern {
             grid,
-            rows,
-            columns,
             smudges,
             summary: None,
             reflection: None,
         };
         result.reflection = result.find_reflection();
-        result.summary = Some(result.summarize());
-
-        result
-    }
-
-    /// Parse Patterns separated by empty lines
-    fn parse_patterns(input: &Vec<String>, smudges: Int) -> Vec<Pattern> {
-        let mut result: Vec<Pattern> = Vec::new();
-        let mut current: Vec<String> = Vec::new();
-        for line in input {
-            if line.is_empty() {
-                result.push(Pattern::parse_pattern(current.clone(), smudges));
-                current.clear();
-            } else {
-                current.push(line.clone());
-            }
-        }
-        if !current.is_empty() {
-            result.push(Pattern::parse_pattern(current.clone(), smudges));
-            current.clear();
+        if result.reflection.is_none() {
+            return Err(input.error_at(0, "a pattern with a line of reflection"));
         }
+        result.summary = Some(result.summarize());
 
-        result
+        Ok(result)
     }
 
-    fn compare(a: &Vec<char>, b: &Vec<char>) -> Int {
-        assert!(a.len() == b.len());
-        let mut differences = 0;
-        for i in 0..a.len() {
-            if a[i] != b[i] {
-                differences += 1;
-            }
-        }
-        differences
+    /// Count the cells in which two rows differ
+    fn compare<'a>(a: impl Iterator<Item = &'a char>, b: impl Iterator<Item = &'a char>) -> Int {
+        a.zip(b).filter(|(a, b)| a != b).count() as Int
     }
 
-    fn get_row(&self, index: usize) -> &Vec<char> {
-        assert!(index < self.rows);
-        &self.grid[index]
-    }
-
-    fn get_column(&self, index: usize) -> Vec<char> {
-        assert!(index < self.columns);
-        let mut result: Vec<char> = Vec::new();
-        for i in 0..self.rows {
-            result.push(self.grid[i][index]);
-        }
-        result
+    /// Return the row after which `grid` is reflected, with exactly `smudges` imperfections
+    fn find_reflection_row(grid: &Map<char>, smudges: Int) -> Option<usize> {
+        let rows = grid.get_rows();
+        (0..rows - 1).find(|&i| {
+            let differences: Int = (0..=i)
+                .rev()
+                .zip(i + 1..rows)
+                .map(|(a, b)| Pattern::compare(grid.row(a), grid.row(b)))
+                .sum();
+            differences == smudges
+        })
     }
 
     fn find_reflection(&self) -> Option<Line> {
-        let columns: Grid<char> = (0..self.columns).map(|i| self.get_column(i)).collect();
-        let mut result = None;
-
-        // Check whether any 2 rows form a reflection, with exactly smudges amount of imperfections
-        for i in 0..self.rows - 1 {
-            let a = self.get_row(i);
-            let b = self.get_row(i + 1);
-            let mut diff = Pattern::compare(a, b);
-            for j in 1..self.rows - 1 - i {
-                if diff > self.smudges || i < j {
-                    break;
-                }
-                let c = self.get_row(i - j);
-                let d = self.get_row(i + 1 + j);
-                diff += Pattern::compare(&c, &d);
-            }
-            if diff == self.smudges {
-                result = Some(Line::Row(i as Int));
-                break;
-            }
-        }
-
-        if result.is_none() {
-            // Check whether any 2 columns form a reflection, with exactly smudges amount of imperfections
-            for i in 0..self.columns - 1 {
-                let a = &columns[i];
-                let b = &columns[i + 1];
-                let mut diff = Pattern::compare(&a, &b);
-                for j in 1..self.columns - 1 - i {
-                    if diff > self.smudges || i < j {
-                        break;
-                    }
-                    let c = &columns[i - j];
-                    let d = &columns[i + 1 + j];
-                    diff += Pattern::compare(&c, &d);
-                }
-                if diff == self.smudges {
-                    result = Some(Line::Column(i as Int));
-                    break;
-                }
-            }
-        }
-
-        result
+        Pattern::find_reflection_row(&self.grid, self.smudges)
+            .map(|i| Line::Row(i as Int))
+            .or_else(|| {
+                Pattern::find_reflection_row(&self.grid.transpose(), self.smudges)
+                    .map(|i| Line::Column(i as Int))
+            })
     }
 
     fn summarize(&self) -> Int {
@@ -227,17 +167,21 @@ impl Solution for Problem {
         )
     }
 
-    fn solve_part_one(&self, input: Input, is_example: bool) -> Answer {
-        debug!(is_example, &input);
-        let part_1_patterns = Pattern::parse(input, 0);
-        debug!(is_example, &part_1_patterns);
+    fn parse_input(&self, input: Input) -> Result<(), ParseError> {
+        Pattern::parse(input, 0).map(|_| ())
+    }
+
+    fn solve_part_one(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        trace!("{}", input);
+        let part_1_patterns = Pattern::parse(input, 0)?;
+        debug!("{:#?}", part_1_patterns);
         let sum: Int = part_1_patterns.iter().map(|p| p.summary.unwrap()).sum();
-        sum as Answer
+        Ok(sum as Answer)
     }
 
-    fn solve_part_two(&self, input: Input, _is_example: bool) -> Answer {
-        let part_2_patterns = Pattern::parse(input, 1);
+    fn solve_part_two(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let part_2_patterns = Pattern::parse(input, 1)?;
         let sum: Int = part_2_patterns.iter().map(|p| p.summary.unwrap()).sum();
-        sum as Answer
+        Ok(sum as Answer)
     }
 }
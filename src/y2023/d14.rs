@@ -1,111 +1,47 @@
 use crate::define_examples;
+use crate::grid::{Direction, Map, Point};
 use crate::*;
 
 static NUMBER_OF_CYCLES: Int = 1000000000;
 
-type Grid<T> = Vec<Vec<T>>;
-
-#[derive(PartialEq, Debug, Clone)]
-enum Direction {
-    North,
-    East,
-    South,
-    West,
-}
-
-use std::{collections::HashMap, fmt::Debug};
+use std::fmt::Debug;
 
 use Direction::*;
 
-#[derive(PartialEq, Debug, Clone, Eq, Hash)]
-struct Point {
-    x: Int,
-    y: Int,
-}
-
-impl Point {
-    fn move_to(&self, direction: Direction) -> Point {
-        match direction {
-            North => Point {
-                x: self.x,
-                y: self.y - 1,
-            },
-            East => Point {
-                x: self.x + 1,
-                y: self.y,
-            },
-            South => Point {
-                x: self.x,
-                y: self.y + 1,
-            },
-            West => Point {
-                x: self.x - 1,
-                y: self.y,
-            },
-        }
-    }
-
-    fn new(x: Int, y: Int) -> Point {
-        Point { x, y }
+define_convertable_enum! {
+    Terrain {
+        RoundedRock(Option<Point>) => 'O',
+        CubeShapedRock(Option<Point>) => '#',
+        EmptySpace(Option<Point>) => '.',
     }
 }
 
-#[derive(PartialEq, Debug, Clone, Eq, Hash)]
-enum Terrain {
-    RoundedRock(Option<Point>),
-    CubeShapedRock(Option<Point>),
-    EmptySpace(Option<Point>),
-}
-
-impl Terrain {
-    fn from_char(c: char) -> Terrain {
-        use Terrain::*;
-        match c {
-            'O' => RoundedRock(None),
-            '#' => CubeShapedRock(None),
-            '.' => EmptySpace(None),
-            _ => panic!("Invalid Terrain char: '{}'.", c),
-        }
-    }
-
-    fn to_char(&self) -> char {
-        use Terrain::*;
-        match &self {
-            RoundedRock(_) => 'O',
-            CubeShapedRock(_) => '#',
-            EmptySpace(_) => '.',
-        }
-    }
-}
-
-#[derive(PartialEq, Clone)]
+#[derive(PartialEq, Eq, Hash, Clone)]
 struct Platform {
     rows: usize,
     columns: usize,
-    grid: Grid<Terrain>,
+    grid: Map<Terrain>,
 }
 
 impl Debug for Platform {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Platform({}x{}):\n{}",
-            self.rows,
-            self.columns,
-            grid_to_string(&self.grid)
+            "Platform({}x{}):\n{}\n",
+            self.rows, self.columns, self.grid
         )
     }
 }
 
 impl Platform {
-    fn parse(input: Input) -> Platform {
+    fn parse(input: Input) -> Result<Platform, ParseError> {
         Platform::from_strings(input.lines().map(|s| s.to_string()).collect::<Vec<_>>())
     }
 
     fn run_spin_cycle(&mut self) {
-        vec![North, West, South, East]
-            .iter()
-            .for_each(|d| self.tilt(d.clone()));
+        [North, West, South, East]
+            .into_iter()
+            .for_each(|d| self.tilt(d));
     }
 
     fn tilt(&mut self, direction: Direction) {
@@ -148,21 +84,21 @@ impl Platform {
     }
 
     fn tilt_terrain_at_point(&mut self, point: &Point, direction: &Direction) {
-        let mut point = point.clone();
-        while self.can_move(&point, direction.clone()) {
-            let to = point.move_to(direction.clone());
-            self.move_to(&point, direction.clone());
+        let mut point = *point;
+        while self.can_move(&point, *direction) {
+            let to = point.move_to(direction);
+            self.move_to(&point, *direction);
             point = to;
         }
     }
 
     fn move_to(&mut self, from_point: &Point, direction: Direction) {
-        let from = self.get(from_point).unwrap().clone();
-        let to = self.get(&from_point.move_to(direction)).unwrap().clone();
+        let from = *self.get(from_point).unwrap();
+        let to = *self.get(&from_point.move_to(&direction)).unwrap();
         match (&from, &to) {
             (Terrain::RoundedRock(_), Terrain::EmptySpace(Some(to_point))) => {
-                self.set(&to_point, &Terrain::RoundedRock(Some(to_point.clone())));
-                self.set(from_point, &Terrain::EmptySpace(Some(from_point.clone())));
+                self.set(to_point, &Terrain::RoundedRock(Some(*to_point)));
+                self.set(from_point, &Terrain::EmptySpace(Some(*from_point)));
             }
             (Terrain::EmptySpace(_) | Terrain::CubeShapedRock(_), _) => (),
             _ => panic!("Not able to move from '{:?}' to '{:?}'.", from, to),
@@ -171,33 +107,22 @@ impl Platform {
 
     fn can_move(&self, point: &Point, direction: Direction) -> bool {
         let from = self.get(&point).unwrap();
-        let to = self.get(&point.move_to(direction));
+        let to = self.get(&point.move_to(&direction));
         match (from, to) {
             (Terrain::RoundedRock(_), Some(Terrain::EmptySpace(_))) => true,
             _ => false,
         }
     }
 
-    fn point_outside_grid(&self, point: &Point) -> bool {
-        point.x < 0 || point.x >= self.columns as Int || point.y < 0 || point.y >= self.rows as Int
-    }
-
     fn get(&self, point: &Point) -> Option<&Terrain> {
-        let point_outside_grid = self.point_outside_grid(point);
-        if point_outside_grid {
-            None
-        } else {
-            Some(&self.grid[point.y as usize][point.x as usize])
-        }
+        self.grid.get(point.x, point.y)
     }
 
     fn set(&mut self, point: &Point, terrain: &Terrain) {
-        assert!(
-            !self.point_outside_grid(point),
-            "Point not inside grid: '{:?}'.",
-            point
-        );
-        self.grid[point.y as usize][point.x as usize] = terrain.clone();
+        *self
+            .grid
+            .get_mut(point.x, point.y)
+            .unwrap_or_else(|| panic!("Point not inside grid: '{:?}'.", point)) = *terrain;
     }
 
     fn load_at_row(&self, row: Int) -> Option<Int> {
@@ -230,62 +155,39 @@ impl Platform {
                 let point = Point::new(x as Int, y as Int);
                 let terrain = self.get(&point).unwrap();
                 let new_terrain = match terrain {
-                    Terrain::CubeShapedRock(_) => Terrain::CubeShapedRock(Some(point.clone())),
-                    Terrain::RoundedRock(_) => Terrain::RoundedRock(Some(point.clone())),
-                    Terrain::EmptySpace(_) => Terrain::EmptySpace(Some(point.clone())),
+                    Terrain::CubeShapedRock(_) => Terrain::CubeShapedRock(Some(point)),
+                    Terrain::RoundedRock(_) => Terrain::RoundedRock(Some(point)),
+                    Terrain::EmptySpace(_) => Terrain::EmptySpace(Some(point)),
                 };
                 self.set(&point, &new_terrain);
             }
         }
     }
 
-    fn get_total_load_after_cycles(&mut self, number_of_cycles: Int) -> Int {
-        let mut grid_at: HashMap<Grid<Terrain>, Int> = HashMap::new();
-        for current_cycle in 1..number_of_cycles {
-            self.run_spin_cycle();
-            if let Some(previous_grid_at) = grid_at.insert(self.grid.clone(), current_cycle) {
-                let cycles_left = number_of_cycles - current_cycle;
-                let number_of_cycles_in_loop = current_cycle - previous_grid_at;
-                if cycles_left % number_of_cycles_in_loop == 0 {
-                    break;
-                }
-            }
-        }
-        self.get_total_load()
+    fn get_total_load_after_cycles(&self, number_of_cycles: Int) -> Int {
+        let spin_cycle = |platform: &Platform| {
+            let mut platform = platform.clone();
+            platform.run_spin_cycle();
+            platform
+        };
+        cycle::nth_state(self.clone(), spin_cycle, number_of_cycles as usize).get_total_load()
     }
 
-    fn from_strings(input: Vec<String>) -> Platform {
-        let grid: Grid<Terrain> = input
-            .iter()
-            .filter(|s| !s.is_empty())
-            .map(|s| s.chars().map(|c| Terrain::from_char(c)).collect())
-            .collect();
-        let rows = grid.len();
-        let columns = grid.first().unwrap().len();
+    fn from_strings(input: Vec<String>) -> Result<Platform, ParseError> {
+        let grid: Map<Terrain> = InputLines::from(input.join("\n"))
+            .filter_empty_lines()
+            .try_into_map()?;
+        let (rows, columns) = (grid.get_rows(), grid.get_columns());
         let mut result = Platform {
             rows,
             columns,
             grid,
         };
         result.assign_points();
-        result
+        Ok(result)
     }
 }
 
-fn grid_to_string(grid: &Grid<Terrain>) -> String {
-    let mut result: String = String::new();
-    let rows = grid.len();
-    let columns = grid.first().unwrap().len();
-    for y in 0..rows {
-        for x in 0..columns {
-            let terrain = &grid[y][x];
-            result.push(terrain.to_char());
-        }
-        result.push('\n');
-    }
-    result
-}
-
 #[derive(Default)]
 pub struct Problem {}
 
@@ -321,29 +223,34 @@ impl Solution for Problem {
         )
     }
 
-    fn solve_part_one(&self, input: Input, _is_example: bool) -> Answer {
-        let mut platform = Platform::parse(input);
+    fn parse_input(&self, input: Input) -> Result<(), ParseError> {
+        Platform::parse(input).map(|_| ())
+    }
+
+    fn solve_part_one(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let mut platform = Platform::parse(input)?;
         platform.tilt(North);
         let total_load = platform.get_total_load();
-        total_load
+        Ok(total_load)
     }
 
-    fn solve_part_two(&self, input: Input, _is_example: bool) -> Answer {
+    fn solve_part_two(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
         let total_load_after_many_cycles =
-            Platform::parse(input).get_total_load_after_cycles(NUMBER_OF_CYCLES);
-        total_load_after_many_cycles
+            Platform::parse(input)?.get_total_load_after_cycles(NUMBER_OF_CYCLES);
+        Ok(total_load_after_many_cycles)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_tilt_part_one() {
         let d14 = Problem {};
         let mut example_platform =
-            Platform::parse(d14.define_examples().first().unwrap().get_input());
+            Platform::parse(d14.define_examples().first().unwrap().get_input()).unwrap();
         dbg!(&example_platform);
         example_platform.tilt(North);
         dbg!(&example_platform);
@@ -361,7 +268,7 @@ mod tests {
             "#....###..",
             "#....#....",
         ];
-        let example_platform_tilted = Platform::from_strings(example_input_tilted);
+        let example_platform_tilted = Platform::from_strings(example_input_tilted).unwrap();
         //dbg!(&example_platform_tilted);
         for y in 0..example_platform_tilted.rows {
             for x in 0..example_platform_tilted.columns {
@@ -381,7 +288,7 @@ mod tests {
     fn test_tilt_part_two() {
         let d14 = Problem {};
         let mut example_platform =
-            Platform::parse(d14.define_examples().first().unwrap().get_input());
+            Platform::parse(d14.define_examples().first().unwrap().get_input()).unwrap();
         let example_platform_1_cycle = Platform::from_strings(vec_of_strings![
             ".....#....",
             "....#...O#",
@@ -393,7 +300,8 @@ mod tests {
             "......OOOO",
             "#...O###..",
             "#..OO#....",
-        ]);
+        ])
+        .unwrap();
         let example_platform_2_cycle = Platform::from_strings(vec_of_strings![
             ".....#....",
             "....#...O#",
@@ -405,7 +313,8 @@ mod tests {
             ".......OOO",
             "#..OO###..",
             "#.OOO#...O",
-        ]);
+        ])
+        .unwrap();
         let example_platform_3_cycle = Platform::from_strings(vec_of_strings![
             ".....#....",
             "....#...O#",
@@ -417,7 +326,8 @@ mod tests {
             ".......OOO",
             "#...O###.O",
             "#.OOO#...O",
-        ]);
+        ])
+        .unwrap();
         example_platform.run_spin_cycle();
         test!(example_platform_1_cycle, example_platform);
         example_platform.run_spin_cycle();
@@ -425,4 +335,42 @@ mod tests {
         example_platform.run_spin_cycle();
         test!(example_platform_3_cycle, example_platform);
     }
+
+    /// Tilt north by moving rounded rocks up one row at a time, until none can move
+    fn tilt_north_step_by_step(lines: &[String]) -> String {
+        let mut rows: Vec<Vec<char>> = lines.iter().map(|line| line.chars().collect()).collect();
+        let mut moved = true;
+        while moved {
+            moved = false;
+            for y in 1..rows.len() {
+                for x in 0..rows[y].len() {
+                    if rows[y][x] == 'O' && rows[y - 1][x] == '.' {
+                        (rows[y][x], rows[y - 1][x]) = ('.', 'O');
+                        moved = true;
+                    }
+                }
+            }
+        }
+        rows.iter()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Platforms of up to 8 by 8 chars
+    fn platforms() -> impl Strategy<Value = Vec<String>> {
+        (1..=8usize, 1..=8usize).prop_flat_map(|(columns, rows)| {
+            let row = prop::string::string_regex(&format!("[.#O]{{{}}}", columns)).unwrap();
+            prop::collection::vec(row, rows)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn tilt_matches_step_by_step(lines in platforms()) {
+            let mut platform = Platform::from_strings(lines.clone()).unwrap();
+            platform.tilt(North);
+            prop_assert_eq!(tilt_north_step_by_step(&lines), platform.grid.to_string());
+        }
+    }
 }
@@ -1,4 +1,6 @@
 use crate::*;
+use nom::combinator::recognize;
+use nom::sequence::pair;
 use std::fmt::Debug;
 
 type Int = i32;
@@ -67,7 +69,7 @@ impl Box {
             .enumerate()
             .map(|(i, l)| focusing_power_from_box * (i + 1) as Int * l.focal_length)
             .sum();
-        debug!(false, focussing_power);
+        trace!("{}", focussing_power);
         focussing_power
     }
 }
@@ -116,8 +118,8 @@ impl BoxSequence {
         } else {
             panic!("Invalid step: '{:?}'.", step);
         }
-        debug!(false, "After {:?}:", step.to_string);
-        debug!(false, "{:?}\n", self);
+        trace!("After {:?}:", step.to_string);
+        trace!("{:?}", self);
     }
 
     fn remove_lens(&mut self, step: &Step) {
@@ -159,7 +161,7 @@ impl Step {
 
     fn from_string_slice_list(input: &str) -> InitializationSequence {
         input
-            .replace("\n", "")
+            .trim_end()
             .split(",")
             .collect::<Vec<&str>>()
             .iter()
@@ -196,6 +198,14 @@ impl Step {
     }
 }
 
+/// Check that every step is an operation: a label followed by `-`, or by `=` and a focal length
+/// from 1 to 9
+fn check_operations(input: &str) -> Result<(), ParseError> {
+    let focal_length = recognize(one_of("123456789"));
+    let operation = pair(alpha1, alt((tag("-"), preceded(tag("="), focal_length))));
+    parsing::parse_all(input, separated_list1(tag(","), operation)).map(|_| ())
+}
+
 fn sum_steps(initialization_sequence: &InitializationSequence) -> Int {
     initialization_sequence.iter().map(|s| s.hash_value).sum()
 }
@@ -225,6 +235,26 @@ mod tests {
             test!(hash_value, Step::from_string_slice(input).hash_value, input);
         }
     }
+
+    #[test]
+    fn test_invalid_steps() {
+        let d15 = Problem::default();
+        for input in ["ab", "rn=1,cm", "qp=0"] {
+            let input = input.to_string();
+            let context = Context::puzzle();
+            test!(true, d15.parse_input(input.clone()).is_err(), input);
+            test!(
+                true,
+                d15.solve_part_one(input.clone(), &context).is_err(),
+                input
+            );
+            test!(
+                true,
+                d15.solve_part_two(input.clone(), &context).is_err(),
+                input
+            );
+        }
+    }
 }
 
 #[derive(Default)]
@@ -253,17 +283,23 @@ impl Solution for Problem {
         )
     }
 
-    fn solve_part_one(&self, input: Input, _is_example: bool) -> Answer {
+    fn parse_input(&self, input: Input) -> Result<(), ParseError> {
+        check_operations(&input)
+    }
+
+    fn solve_part_one(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        check_operations(&input)?;
         let sequence = Step::from_string_list(&input);
         let steps_sum = sum_steps(&sequence);
-        steps_sum as Answer
+        Ok(steps_sum as Answer)
     }
 
-    fn solve_part_two(&self, input: Input, _is_example: bool) -> Answer {
+    fn solve_part_two(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        check_operations(&input)?;
         let sequence = Step::from_string_list(&input);
         let mut box_sequence = BoxSequence::new();
         sequence.iter().for_each(|s| box_sequence.execute(&s));
         let total_focussing_power = box_sequence.get_total_focussing_power();
-        total_focussing_power as Answer
+        Ok(total_focussing_power as Answer)
     }
 }
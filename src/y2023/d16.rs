@@ -15,9 +15,9 @@ struct Terrain {
 }
 
 impl Terrain {
-    fn from_char(c: char) -> Terrain {
+    fn new(the_type: TerrainType) -> Terrain {
         Terrain {
-            the_type: TerrainType::from_char(c),
+            the_type,
             beams: Vec::new(),
             energized: false,
         }
@@ -38,16 +38,15 @@ impl TerrainType {
     fn redirect(&self, beam: &Beam) -> Vec<Beam> {
         use TerrainType::*;
         match (&self, beam) {
-            (NorthEastMirror, North) => vec![East],
-            (NorthEastMirror, East) => vec![North],
-            (NorthEastMirror, South) => vec![West],
-            (NorthEastMirror, West) => vec![South],
-            (NorthWestMirror, North) => vec![West],
-            (NorthWestMirror, East) => vec![South],
-            (NorthWestMirror, South) => vec![East],
-            (NorthWestMirror, West) => vec![North],
-            (NorthSouthSplitter, East | West) => vec![North, South],
-            (EastWestSplitter, North | South) => vec![East, West],
+            (NorthEastMirror, North | South) | (NorthWestMirror, East | West) => {
+                vec![beam.turn_right()]
+            }
+            (NorthEastMirror, East | West) | (NorthWestMirror, North | South) => {
+                vec![beam.turn_left()]
+            }
+            (NorthSouthSplitter, East | West) | (EastWestSplitter, North | South) => {
+                vec![beam.turn_left(), beam.turn_right()]
+            }
             (_, _) => vec![beam.clone()],
         }
     }
@@ -62,23 +61,23 @@ struct ContraptionMap {
 }
 
 impl ContraptionMap {
-    fn parse(input: Input) -> ContraptionMap {
-        let input = input.lines().map(|s| s.to_string()).collect();
-        let map = ContraptionMap::from_strings(&input);
-        map
+    fn parse(input: Input) -> Result<ContraptionMap, ParseError> {
+        let lines = InputLines::from(input.clone()).filter_empty_lines();
+        if lines.is_empty() {
+            return Err(ParseError::at(&input, "", "a contraption"));
+        }
+        let map: Map<TerrainType> = lines.try_into_map()?;
+        Ok(ContraptionMap::from_map(&map))
     }
 
-    fn from_strings(input: &Vec<String>) -> ContraptionMap {
-        let grid: Grid<Terrain> = input
-            .iter()
-            .filter(|s| !s.is_empty())
-            .map(|s| s.chars().map(|c| Terrain::from_char(c)).collect::<Vec<_>>())
+    fn from_map(map: &Map<TerrainType>) -> ContraptionMap {
+        let grid: Grid<Terrain> = map
+            .rows()
+            .map(|row| row.map(|&the_type| Terrain::new(the_type)).collect())
             .collect();
-        let rows = grid.len() as Int;
-        let columns = grid.first().unwrap().len() as Int;
         ContraptionMap {
-            rows,
-            columns,
+            rows: map.get_rows() as Int,
+            columns: map.get_columns() as Int,
             grid,
             visited: Points::new(),
         }
@@ -92,33 +91,13 @@ impl ContraptionMap {
         &mut self.grid[point.y as usize][point.x as usize]
     }
 
-    fn get_energy_map(&self) -> String {
-        self.grid
-            .iter()
-            .map(|v| {
-                v.iter()
-                    .map(|t| if t.energized { '#' } else { '.' })
-                    .collect::<String>()
-            })
-            .collect::<Vec<String>>()
-            .join("\n")
-    }
-
-    fn get_terrain_map(&self) -> String {
-        self.grid
-            .iter()
-            .map(|v| v.iter().map(|t| t.the_type.to_char()).collect::<String>())
-            .collect::<Vec<String>>()
-            .join("\n")
-    }
-
     fn within_grid(&self, point: &Point) -> bool {
         point.x >= 0 && point.x < self.columns && point.y >= 0 && point.y < self.rows
     }
 
     fn shoot_beam(&mut self, from: &Point, beam: Beam) {
         let mut points: Points = Points::new();
-        debug!(false, "shoot({:?}, {:?})", from, beam);
+        trace!("shoot({:?}, {:?})", from, beam);
         if self.within_grid(from) {
             self.visited.insert(*from);
             let current_terrain: &Terrain = self.get_terrain(from);
@@ -137,7 +116,7 @@ impl ContraptionMap {
             }
         }
 
-        debug!(false, "shoot({:?}, {:?}) -> {:?}", from, beam, points);
+        trace!("shoot({:?}, {:?}) -> {:?}", from, beam, points);
     }
 
     fn get_amount_of_energized_tiles(&mut self, point: &Point, beam: Beam) -> Int {
@@ -213,39 +192,81 @@ impl Solution for Problem {
         )
     }
 
-    fn solve_part_one(&self, input: Input, is_example: bool) -> Answer {
-        let mut map = ContraptionMap::parse(input);
+    /// A contraption of `size` by `size`, in which about one in ten cells is a mirror or splitter
+    fn generate_input(&self, rng: &mut Rng, size: usize) -> Option<Input> {
+        let size = size.max(1);
+        Some(rng.grid(size, size, |rng| {
+            if rng.chance(0.1) {
+                *rng.choose(&['/', '\\', '|', '-'])
+            } else {
+                '.'
+            }
+        }))
+    }
+
+    fn parse_input(&self, input: Input) -> Result<(), ParseError> {
+        ContraptionMap::parse(input).map(|_| ())
+    }
+
+    fn solve_part_one(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let mut map = ContraptionMap::parse(input)?;
         map.shoot_beam(&Point::new(0, 0), East);
-        let amount_of_energized_tiles = map.get_amount_of_energized_tiles(&Point::new(0, 0), East);
-        if is_example {
-            let terrain_map = map.get_terrain_map();
-            let energy_map = map.get_energy_map();
-            println!("Terrain map:\n{}\n", terrain_map);
-            println!("Energy map:\n{}\n", energy_map);
-            let example_expected_energized_map = vec![
-                "######....",
-                ".#...#....",
-                ".#...#####",
-                ".#...##...",
-                ".#...##...",
-                ".#...##...",
-                ".#..####..",
-                "########..",
-                ".#######..",
-                ".#...#.#..",
-            ]
-            .iter()
-            .map(|s| s.to_string())
-            .collect::<Vec<String>>()
-            .join("\n");
-            test!(example_expected_energized_map, energy_map, "energy_map");
-        }
-        amount_of_energized_tiles
+        Ok(map.get_amount_of_energized_tiles(&Point::new(0, 0), East))
     }
 
-    fn solve_part_two(&self, input: Input, _is_example: bool) -> Answer {
-        let mut map = ContraptionMap::parse(input);
+    fn solve_part_two(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let mut map = ContraptionMap::parse(input)?;
         let most_amount_energized = map.get_most_amount_of_energized_tiles();
-        most_amount_energized
+        Ok(most_amount_energized)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    impl ContraptionMap {
+        fn get_energy_map(&self) -> String {
+            self.grid
+                .iter()
+                .map(|v| {
+                    v.iter()
+                        .map(|t| if t.energized { '#' } else { '.' })
+                        .collect::<String>()
+                })
+                .collect::<Vec<String>>()
+                .join("\n")
+        }
+
+        fn get_terrain_map(&self) -> String {
+            self.grid
+                .iter()
+                .map(|v| v.iter().map(|t| t.the_type.to_char()).collect::<String>())
+                .collect::<Vec<String>>()
+                .join("\n")
+        }
+    }
+
+    #[test]
+    fn test_energy_map() {
+        let d16 = Problem {};
+        let mut map =
+            ContraptionMap::parse(d16.define_examples().first().unwrap().get_input()).unwrap();
+        map.shoot_beam(&Point::new(0, 0), East);
+        println!("Terrain map:\n{}\n", map.get_terrain_map());
+        let expected_energy_map = [
+            "######....",
+            ".#...#....",
+            ".#...#####",
+            ".#...##...",
+            ".#...##...",
+            ".#...##...",
+            ".#..####..",
+            "########..",
+            ".#######..",
+            ".#...#.#..",
+        ]
+        .join("\n");
+        test!(expected_energy_map, map.get_energy_map());
     }
 }
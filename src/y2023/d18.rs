@@ -1,16 +1,48 @@
-use std::{
-    cmp::{max, min},
-    iter::once,
-};
+use std::cmp::{max, min};
 
 use colored::{Colorize, CustomColor};
 use grid::*;
 use hex_color::HexColor;
 
 use crate::*;
+use nom::bytes::complete::take_while_m_n;
+use nom::character::complete::space1;
+use nom::combinator::{map_opt, recognize};
+use nom::sequence::{delimited, pair};
 
 type Int = i64;
 
+/// A line of the dig plan, such as `R 6 (#70c710)`
+struct Instruction {
+    direction: Direction,
+    length: Int,
+    color: String,
+}
+
+impl Instruction {
+    fn parse(input: &str) -> IResult<&str, Instruction> {
+        // The color is also a length of 5 hex digits and a direction of `0` to `3`
+        let hex_length = take_while_m_n(5, 5, |c: char| c.is_ascii_hexdigit());
+        let color = recognize(pair(hex_length, one_of("0123")));
+        map(
+            tuple((
+                terminated(map_opt(one_of("UDLR"), Direction::from_letter), space1),
+                terminated(map_res(digit1, str::parse::<Int>), space1),
+                delimited(tag("("), recognize(pair(tag("#"), color)), tag(")")),
+            )),
+            |(direction, length, color)| Instruction {
+                direction,
+                length,
+                color: color.to_string(),
+            },
+        )(input)
+    }
+}
+
+fn parse_dig_plan(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    parsing::parse_all(input, parsing::lines(Instruction::parse))
+}
+
 #[derive(Debug)]
 struct Point {
     point: grid::Point,
@@ -56,125 +88,100 @@ impl Polygon {
         Polygon { points: Vec::new() }
     }
 
-    fn translate(self, x: Int, y: Int) -> Polygon {
-        Polygon {
-            points: self
-                .points
-                .iter()
-                .map(|p| Point {
-                    point: p.point.translate(x, y),
-                    terrain: p.terrain.clone(),
-                })
-                .collect(),
-        }
-    }
-
-    fn translate_to_px_py(self) -> Polygon {
-        let mut min_x = Int::MAX;
-        let mut min_y = Int::MAX;
-        for p in &self.points {
-            min_x = min(min_x, p.point.x);
-            min_y = min(min_y, p.point.y);
-        }
-        self.translate(-min_x, -min_y)
+    fn vertices(&self) -> Vec<grid::Point> {
+        self.points.iter().map(|p| p.point).collect()
     }
 
-    fn print(&self) {
-        let mut max_x = 0;
-        let mut max_y = 0;
-        for p in &self.points {
-            max_x = max(max_x, p.point.x);
-            max_y = max(max_y, p.point.y);
-        }
-        for y in 0..=max_y {
-            for x in 0..=max_x {
-                if let Some(p) = self
-                    .points
-                    .iter()
-                    .find(|p| p.point.x == x && p.point.y == y)
-                {
-                    print!("{}", p.terrain.to_string());
-                } else {
-                    print!("{}", Terrain::Ground.to_string())
+    /// Draw the polygon as a map of terrain, where each edge has the color of the vertex it
+    /// starts at
+    fn draw(&self) -> String {
+        let vertices = self.vertices();
+        let (xs, ys) = (vertices.iter().map(|p| p.x), vertices.iter().map(|p| p.y));
+        let (min_x, max_x) = (xs.clone().min().unwrap_or(0), xs.max().unwrap_or(0));
+        let (min_y, max_y) = (ys.clone().min().unwrap_or(0), ys.max().unwrap_or(0));
+        let between = |p: Int, q: Int, r: Int| min(p, q) <= r && r <= max(p, q);
+        let mut map = String::new();
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let edge = (0..vertices.len()).find(|&i| {
+                    let (a, b) = (vertices[i], vertices[(i + 1) % vertices.len()]);
+                    between(a.x, b.x, x) && between(a.y, b.y, y)
+                });
+                match edge {
+                    Some(i) => map.push_str(&self.points[i].terrain.to_string()),
+                    None => map.push_str(&Terrain::Ground.to_string()),
                 }
             }
-            println!();
+            map.push('\n');
         }
+        map
     }
 
-    fn calc_area(&self) -> Int {
-        //Shoelace formule to calculate area of polygon
-        if self.points.is_empty() {
-            0
-        } else {
-            let points: Vec<_> = self.points.iter().chain(once(&self.points[0])).collect();
-            let b = {
-                let mut sum = 0;
-                for i in 0..points.len() - 1 {
-                    let (p_0, p_1) = (points[i].point, points[i + 1].point);
-                    sum += p_0.distance_to(&p_1) as Int;
-                }
-                sum
-            };
-            let mut area: Int = 0;
-            for i in 0..self.points.len() {
-                let (p_0, p_1) = (points[i].point, points[i + 1].point);
-                area += p_0.x * p_1.y - p_1.x * p_0.y;
-            }
-            area = (area / 2).abs();
-            // A = i + b/2 - 1
-            // i = A-b/2+1
-            // Filled polygon = i + b
-            b + area - b / 2 + 1
-        }
+    /// The number of tiles dug out, the trench and the interior, or `None` if it overflows
+    fn calc_area(&self) -> Option<Int> {
+        geometry::lattice_points(&self.vertices())
     }
 
-    fn from_dig_plan(dig_plan: &Vec<String>, swapped: bool) -> Polygon {
+    /// The polygon of the vertices of the trench, or `None` if one does not fit in an Int
+    fn from_dig_plan(dig_plan: &[Instruction], swapped: bool) -> Option<Polygon> {
         let mut polygon: Polygon = Polygon::new();
         let mut p = grid::Point::new(0, 0);
         for op in dig_plan {
-            let (mut d, mut l, c) = {
-                let mut i = op.split_whitespace();
-                (
-                    i.next().unwrap(),
-                    i.next().unwrap().parse::<Int>().unwrap(),
-                    i.next().unwrap(),
-                )
-            };
-            let c = &c[1..c.len() - 1].to_string();
+            let (mut direction, mut l, c) = (op.direction, op.length, &op.color);
             if swapped {
                 let hex_l = &c[1..6];
                 let hex_d = &c[6..7];
                 l = Int::from_str_radix(hex_l, 16).unwrap();
-                d = match hex_d {
-                    "0" => "R",
-                    "1" => "D",
-                    "2" => "L",
-                    "3" => "U",
+                direction = match hex_d {
+                    "0" => East,
+                    "1" => South,
+                    "2" => West,
+                    "3" => North,
                     _ => panic!("Invalid hex direction: '{}'", hex_d),
                 };
             }
-            let direction = match d {
-                "R" => East,
-                "D" => South,
-                "U" => North,
-                "L" => West,
-                _ => panic!("Invalid direction: '{}'", d),
-            };
-            if swapped {
-                polygon.points.push(Point::new(p, c.clone()));
-                p = p.move_distance(&direction, l);
+            polygon.points.push(Point::new(p, c.clone()));
+            let unit = direction.to_point();
+            p = grid::Point::new(
+                p.x.checked_add(unit.x.checked_mul(l)?)?,
+                p.y.checked_add(unit.y.checked_mul(l)?)?,
+            );
+        }
+        trace!("{:?}", polygon);
+        Some(polygon)
+    }
+}
+
+/// The error for a dig plan whose trench or lagoon does not fit in an Int
+fn overflow() -> ParseError {
+    ParseError::of_whole_input("a dig plan whose lagoon fits in 64 bits")
+}
+
+/// The steps around a random skyline of `columns` columns with sides of up to `max_length`:
+/// right along the bottom, then left along the tops of the columns, and down to the start
+fn generate_skyline(rng: &mut Rng, columns: usize, max_length: Int) -> Vec<(char, Int)> {
+    let widths: Vec<Int> = (0..columns).map(|_| rng.range(1, max_length)).collect();
+    let mut heights: Vec<Int> = vec![rng.range(1, max_length)];
+    while heights.len() < columns {
+        let height = rng.range(1, max_length);
+        if height != *heights.last().unwrap() {
+            heights.push(height);
+        }
+    }
+    let mut steps = vec![('R', widths.iter().sum()), ('U', heights[columns - 1])];
+    for i in (0..columns).rev() {
+        steps.push(('L', widths[i]));
+        if i > 0 {
+            let (from, to) = (heights[i], heights[i - 1]);
+            steps.push(if to > from {
+                ('U', to - from)
             } else {
-                for _ in 0..l {
-                    polygon.points.push(Point::new(p, c.clone()));
-                    p = p.move_to(&direction);
-                }
-            }
+                ('D', from - to)
+            });
         }
-        debug!(false, "{:?}", &polygon);
-        polygon = polygon.translate_to_px_py();
-        polygon
     }
+    steps.push(('D', heights[0]));
+    steps
 }
 
 #[derive(Default)]
@@ -216,21 +223,35 @@ impl Solution for Problem {
     )
     }
 
-    fn solve_part_one(&self, input: Input, is_example: bool) -> Answer {
-        let dig_plan = input.lines().map(|s| s.to_string()).collect();
-        let polygon = Polygon::from_dig_plan(&dig_plan, false);
-        debug!(is_example, &polygon);
-        if is_example {
-            polygon.print();
+    /// A dig plan around a skyline of `size` columns, with colors that encode another skyline
+    fn generate_input(&self, rng: &mut Rng, size: usize) -> Option<Input> {
+        let columns = size.max(1);
+        let plan = generate_skyline(rng, columns, 10);
+        let swapped = generate_skyline(rng, columns, 0xfffff / columns as Int);
+        let mut input = String::new();
+        for ((d, l), (hex_d, hex_l)) in plan.into_iter().zip(swapped) {
+            let hex_d = "RDLU".find(hex_d).unwrap();
+            input += &format!("{} {} (#{:05x}{})\n", d, l, hex_l, hex_d);
+        }
+        Some(input)
+    }
+
+    fn parse_input(&self, input: Input) -> Result<(), ParseError> {
+        parse_dig_plan(&input).map(|_| ())
+    }
+
+    fn solve_part_one(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let dig_plan = parse_dig_plan(&input)?;
+        let polygon = Polygon::from_dig_plan(&dig_plan, false).ok_or_else(overflow)?;
+        if log_enabled!(Level::Debug) {
+            debug!("\n{}", polygon.draw());
         }
-        let a = polygon.calc_area();
-        a
+        polygon.calc_area().ok_or_else(overflow)
     }
 
-    fn solve_part_two(&self, input: Input, _is_example: bool) -> Answer {
-        let dig_plan = input.lines().map(|s| s.to_string()).collect();
-        let polygon = Polygon::from_dig_plan(&dig_plan, true);
-        let a = polygon.calc_area();
-        a
+    fn solve_part_two(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let dig_plan = parse_dig_plan(&input)?;
+        let polygon = Polygon::from_dig_plan(&dig_plan, true).ok_or_else(overflow)?;
+        polygon.calc_area().ok_or_else(overflow)
     }
 }
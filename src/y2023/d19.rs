@@ -1,4 +1,6 @@
+use crate::interval::{Interval, IntervalSet};
 use crate::*;
+use parsing::lines;
 type Workflows = HashMap<String, Workflow>;
 type Path = Vec<Condition>;
 
@@ -30,11 +32,8 @@ enum Condition {
 
 impl Condition {
     fn parse(input: &str) -> IResult<&str, Condition> {
-        let (input, (var, op, val)) = tuple((
-            one_of("xmas"),
-            one_of("<>"),
-            map_res(digit1, |s: &str| s.parse()),
-        ))(input)?;
+        let (input, (var, op, val)) =
+            tuple((one_of("xmas"), one_of("<>"), parsing::number))(input)?;
         let condition = match op {
             '>' => Condition::GreaterThan(var, val),
             '<' => Condition::LessThan(var, val),
@@ -52,6 +51,20 @@ impl Condition {
         }
     }
 
+    /// The variable of this Condition, and the values of it that satisfy the Condition
+    fn accepted(&self) -> (char, Interval) {
+        match *self {
+            Condition::GreaterThan(var, val) => {
+                (var, Interval::new(val.saturating_add(1), Int::MAX))
+            }
+            Condition::LessThan(var, val) => (var, Interval::new(Int::MIN, val)),
+            Condition::GreaterThanOrEqual(var, val) => (var, Interval::new(val, Int::MAX)),
+            Condition::LessThanOrEqual(var, val) => {
+                (var, Interval::new(Int::MIN, val.saturating_add(1)))
+            }
+        }
+    }
+
     fn opposite(&self) -> Condition {
         match self {
             Condition::GreaterThan(var, val) => Condition::LessThanOrEqual(*var, *val),
@@ -93,38 +106,21 @@ impl Rule {
     }
 }
 
-#[derive(Clone, Debug)]
+/// A Part, such as `{x=787,m=2655,a=1222,s=2876}`
+#[derive(Clone, Debug, AocParse)]
+#[aoc(prefix = "{", separator = ",", suffix = "}")]
 struct Part {
+    #[aoc(prefix = "x=")]
     x: Int,
+    #[aoc(prefix = "m=")]
     m: Int,
+    #[aoc(prefix = "a=")]
     a: Int,
+    #[aoc(prefix = "s=")]
     s: Int,
 }
 
 impl Part {
-    fn parse(input: &str) -> IResult<&str, Part> {
-        let parse_num = |input| map_res(digit1, str::parse::<Int>)(input);
-
-        let (input, (x, m, a, s)) = tuple((
-            preceded(tag("{x="), parse_num),
-            preceded(tag(",m="), parse_num),
-            preceded(tag(",a="), parse_num),
-            preceded(tag(",s="), terminated(parse_num, tag("}"))),
-        ))(input)?;
-
-        Ok((input, Part { x, m, a, s }))
-    }
-
-    fn set(&mut self, var: &char, val: Int) {
-        match var {
-            'x' => self.x = val,
-            'm' => self.m = val,
-            'a' => self.a = val,
-            's' => self.s = val,
-            _ => unreachable!(),
-        }
-    }
-
     fn get(&self, var: &char) -> Int {
         match var {
             'x' => self.x,
@@ -166,6 +162,15 @@ impl Workflow {
         Ok((input, Workflow { name, rules }))
     }
 
+    /// The Workflows this Workflow can send a part to
+    fn next(&self) -> impl Iterator<Item = &str> {
+        self.rules.iter().filter_map(|rule| match rule {
+            Rule::Evaluation(_, Destination::Workflow(name))
+            | Rule::Tautology(Destination::Workflow(name)) => Some(name.as_str()),
+            _ => None,
+        })
+    }
+
     fn evaluate(&self, part: &Part) -> Destination {
         self.rules
             .iter()
@@ -214,51 +219,75 @@ fn generate_accepted_paths(
 fn compute_distinct_combinations(paths: &Vec<Path>) -> Int {
     let mut sum: Int = 0;
     for path in paths {
-        let mut min_part = Part {
-            x: 1,
-            m: 1,
-            a: 1,
-            s: 1,
-        };
-        let mut max_part = Part {
-            x: 4000,
-            m: 4000,
-            a: 4000,
-            s: 4000,
-        };
+        let mut ratings: HashMap<char, IntervalSet> = "xmas"
+            .chars()
+            .map(|var| (var, Interval::inclusive(1, 4000).into()))
+            .collect();
         for condition in path {
-            match condition {
-                Condition::LessThan(var, val) => {
-                    max_part.set(var, max_part.get(var).min(val - 1));
-                }
-                Condition::LessThanOrEqual(var, val) => {
-                    max_part.set(var, max_part.get(var).min(*val));
-                }
-                Condition::GreaterThan(var, val) => {
-                    min_part.set(var, min_part.get(var).max(val + 1));
-                }
-                Condition::GreaterThanOrEqual(var, val) => {
-                    min_part.set(var, min_part.get(var).max(*val));
-                }
-            }
+            let (var, accepted) = condition.accepted();
+            let rating = ratings.get_mut(&var).unwrap();
+            *rating = rating.intersection(&accepted.into());
         }
-        assert!(min_part.x <= max_part.x);
-        assert!(min_part.m <= max_part.m);
-        assert!(min_part.a <= max_part.a);
-        assert!(min_part.s <= max_part.s);
-        let combinations = (max_part.x - min_part.x + 1)
-            * (max_part.m - min_part.m + 1)
-            * (max_part.a - min_part.a + 1)
-            * (max_part.s - min_part.s + 1);
-        sum += combinations;
+        sum += ratings.values().map(IntervalSet::len).product::<Int>();
     }
     sum
 }
 
-fn get_workflows(input: &String) -> (&str, Workflows) {
-    let (rest, workflows) = separated_list1(tag("\n"), Workflow::parse)(&input).unwrap();
+/// Check that every Workflow ends with a Rule without a Condition, and only sends parts to
+/// Workflows that exist, and that no Workflow reachable from `in` can be reached from itself, so
+/// every part ends up accepted or rejected
+fn check_workflows(workflows: &[Workflow], lines: &InputLines) -> Result<(), ParseError> {
+    let index: HashMap<&str, usize> = workflows
+        .iter()
+        .enumerate()
+        .map(|(i, w)| (w.name.as_str(), i))
+        .collect();
+    for (i, workflow) in workflows.iter().enumerate() {
+        if !matches!(workflow.rules.last(), Some(Rule::Tautology(_))) {
+            return Err(lines.error_at(i, "a last rule without a condition"));
+        }
+        if workflow.next().any(|name| !index.contains_key(name)) {
+            return Err(lines.error_at(i, "destinations that are workflows"));
+        }
+    }
+    let Some(&start) = index.get("in") else {
+        return Err(ParseError::of_whole_input("a workflow named 'in'"));
+    };
+    // Depth-first search, in which a Workflow that is still being visited is reached again
+    // through a cycle
+    let mut visiting = vec![false; workflows.len()];
+    let mut visited = vec![false; workflows.len()];
+    let mut stack = vec![(start, false)];
+    while let Some((i, done)) = stack.pop() {
+        if done {
+            (visiting[i], visited[i]) = (false, true);
+            continue;
+        }
+        if visiting[i] {
+            return Err(lines.error_at(i, "workflows without a cycle"));
+        }
+        if visited[i] {
+            continue;
+        }
+        visiting[i] = true;
+        stack.push((i, true));
+        stack.extend(workflows[i].next().map(|name| (index[name], false)));
+    }
+    Ok(())
+}
+
+/// Parse the Workflows, and after a blank line, the Parts
+fn parse_system(input: Input) -> Result<(Workflows, Vec<Part>), ParseError> {
+    let mut paragraphs = InputLines::from(input).paragraphs().into_iter();
+    let workflow_lines = paragraphs.next().unwrap_or_default();
+    let workflows = workflow_lines.parse_with(|s| lines(Workflow::parse)(s))?;
+    check_workflows(&workflows, &workflow_lines)?;
+    let parts = paragraphs
+        .next()
+        .unwrap_or_default()
+        .parse_with(|s| lines(Part::parse_record)(s))?;
     let workflows: Workflows = workflows.into_iter().map(|w| (w.name.clone(), w)).collect();
-    (rest, workflows)
+    Ok((workflows, parts))
 }
 
 #[derive(Default)]
@@ -303,11 +332,59 @@ impl Solution for Problem {
         )
     }
 
-    fn solve_part_one(&self, input: Input, example: bool) -> Answer {
-        let (rest, workflows) = get_workflows(&input);
-        debug!(example, &workflows);
-        let (_, parts) = separated_list1(tag("\n"), Part::parse)(rest.trim()).unwrap();
-        debug!(example, &parts);
+    /// A tree of `size` workflows starting at `in`, and `size` parts
+    fn generate_input(&self, rng: &mut Rng, size: usize) -> Option<Input> {
+        let mut names = vec!["in".to_string()];
+        while names.len() < size.max(1) {
+            let name = rng.word(3);
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        // Every workflow but `in` is sent to by one earlier workflow
+        let mut destinations: Vec<Vec<String>> = vec![Vec::new(); names.len()];
+        for (i, name) in names.iter().enumerate().skip(1) {
+            destinations[rng.below(i)].push(name.clone());
+        }
+        let mut input = String::new();
+        for (name, mut destinations) in names.iter().zip(destinations) {
+            while destinations.len() < 2 || rng.chance(0.3) {
+                destinations.push(rng.choose(&["A", "R"]).to_string());
+            }
+            rng.shuffle(&mut destinations);
+            let fallback = destinations.pop().unwrap();
+            let rules: Vec<String> = destinations
+                .iter()
+                .map(|destination| {
+                    let category = rng.choose(&['x', 'm', 'a', 's']);
+                    let operator = rng.choose(&['<', '>']);
+                    format!(
+                        "{}{}{}:{}",
+                        category,
+                        operator,
+                        rng.range(1, 4000),
+                        destination
+                    )
+                })
+                .collect();
+            input += &format!("{}{{{},{}}}\n", name, rules.join(","), fallback);
+        }
+        input.push('\n');
+        for _ in 0..size.max(1) {
+            let [x, m, a, s] = [(); 4].map(|_| rng.range(1, 4000));
+            input += &format!("{{x={},m={},a={},s={}}}\n", x, m, a, s);
+        }
+        Some(input)
+    }
+
+    fn parse_input(&self, input: Input) -> Result<(), ParseError> {
+        parse_system(input).map(|_| ())
+    }
+
+    fn solve_part_one(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let (workflows, parts) = parse_system(input)?;
+        debug!("{:#?}", workflows);
+        debug!("{:#?}", parts);
 
         let sum_total_ratings: Int = parts
             .iter()
@@ -315,15 +392,15 @@ impl Solution for Problem {
             .map(|p| p.total_rating())
             .sum();
 
-        sum_total_ratings
+        Ok(sum_total_ratings)
     }
 
-    fn solve_part_two(&self, input: Input, example: bool) -> Answer {
-        let workflows = get_workflows(&input).1;
+    fn solve_part_two(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let workflows = parse_system(input)?.0;
         let mut paths = Vec::new();
         generate_accepted_paths(&mut paths, Path::new(), "in", &workflows);
-        debug!(example, paths);
+        debug!("{:#?}", paths);
         let sum = compute_distinct_combinations(&paths);
-        sum
+        Ok(sum)
     }
 }
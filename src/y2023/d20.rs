@@ -5,7 +5,9 @@ type Name = String;
 type Modules = HashMap<Name, Module>;
 type Memory = HashMap<Name, PulseKind>;
 type RxSenders = Vec<Name>;
-static DEBUG: bool = false;
+/// The counters of the puzzle input have 12 bits, so they each send a high pulse towards `rx`
+/// within this many presses
+const MAX_PRESSES: Int = 1 << 12;
 
 #[derive(Copy, Clone, Debug)]
 enum State {
@@ -58,12 +60,6 @@ struct Module {
     destinations: Vec<Name>,
 }
 
-impl Parse for Module {
-    fn parse(input: Input) -> Self {
-        Module::parse_module(&input).unwrap().1
-    }
-}
-
 impl Module {
     fn parse_module(input: &str) -> IResult<&str, Module> {
         // Parsing "broadcaster" as a special case
@@ -165,14 +161,17 @@ struct System {
 }
 
 impl Parse for System {
-    fn parse(input: Input) -> Self {
-        let lines: Vec<String> = InputLines::from(input).into();
-        let modules: Modules = lines
+    fn parse(input: Input) -> Result<Self, ParseError> {
+        let modules: Modules = parsing::parse_all(&input, parsing::lines(Module::parse_module))?
             .into_iter()
-            .map(Module::parse)
             .map(|m| (m.name.clone(), m))
             .collect();
-        System {
+        if has_endless_cycle(&modules) {
+            return Err(ParseError::of_whole_input(
+                "no cycle of only conjunctions and the broadcaster",
+            ));
+        }
+        Ok(System {
             modules,
             pulses: Queue::new(),
             pulse_history: Vec::new(),
@@ -180,8 +179,52 @@ impl Parse for System {
             high_pulses: 0,
             rx_senders: RxSenders::new(),
             times_pressed: 0,
+        })
+    }
+}
+
+/// Whether there is a cycle of conjunctions and the broadcaster, which send a pulse for every pulse
+/// they get, so a pulse that reaches it never stops going around
+fn has_endless_cycle(modules: &Modules) -> bool {
+    let forwards = |name: &Name| {
+        modules.get(name).is_some_and(|module| {
+            matches!(
+                module.kind,
+                ModuleKind::Conjuction(_) | ModuleKind::Broadcaster
+            )
+        })
+    };
+    // Remove the modules without incoming pulses from the others, until only cycles remain
+    let mut incoming: HashMap<&Name, usize> = modules
+        .keys()
+        .filter(|name| forwards(name))
+        .map(|name| (name, 0))
+        .collect();
+    for name in incoming.clone().keys() {
+        for destination in &modules[*name].destinations {
+            if let Some(count) = incoming.get_mut(destination) {
+                *count += 1;
+            }
         }
     }
+    let mut removable: Vec<&Name> = incoming
+        .iter()
+        .filter(|(_, count)| **count == 0)
+        .map(|(name, _)| *name)
+        .collect();
+    let mut removed = 0;
+    while let Some(name) = removable.pop() {
+        removed += 1;
+        for destination in &modules[name].destinations {
+            if let Some(count) = incoming.get_mut(destination) {
+                *count -= 1;
+                if *count == 0 {
+                    removable.push(destination);
+                }
+            }
+        }
+    }
+    removed < incoming.len()
 }
 
 impl System {
@@ -216,21 +259,18 @@ impl System {
     /// Press the button and run the System, until all pulses have been handled, `times` times after each other
     fn press_button_repeatedly(&mut self, times: Int) {
         for i in 0..times {
-            debug!(DEBUG, "Press button: {}", i);
+            trace!("Press button: {}", i);
 
             self.times_pressed += 1;
             self.press_button();
-            debug!(
-                DEBUG,
-                "(high: {}, low: {})\n", self.high_pulses, self.low_pulses
-            );
+            trace!("(high: {}, low: {})", self.high_pulses, self.low_pulses);
         }
     }
 
     fn run_until_all_pulses_handled(&mut self) {
         while !self.pulses.is_empty() {
             let pulse = self.pulses.pop_front().unwrap();
-            debug!(DEBUG, &pulse);
+            trace!("{:?}", pulse);
 
             let mut destination = self
                 .modules
@@ -252,6 +292,103 @@ impl System {
         }
     }
 
+    /// The names of `name` and all modules that send pulses to it, directly or indirectly
+    fn upstream_of(&self, name: &str) -> Vec<Name> {
+        let mut upstream: Vec<Name> = vec![name.to_string()];
+        let mut i = 0;
+        while i < upstream.len() {
+            for module in self.modules.values() {
+                if module.destinations.contains(&upstream[i]) && !upstream.contains(&module.name) {
+                    upstream.push(module.name.clone());
+                }
+            }
+            i += 1;
+        }
+        upstream.sort();
+        upstream
+    }
+
+    /// The states of the flip-flops, and the memories of the conjunctions, named in `names`
+    fn state_of(&self, names: &[Name]) -> Vec<bool> {
+        names
+            .iter()
+            .flat_map(|name| match &self.modules[name].kind {
+                ModuleKind::FlipFlop(state) => vec![matches!(state, State::On)],
+                ModuleKind::Conjuction(memory) => {
+                    let mut inputs: Vec<_> = memory.iter().collect();
+                    inputs.sort_by_key(|(input, _)| *input);
+                    inputs
+                        .into_iter()
+                        .map(|(_, kind)| *kind == PulseKind::High)
+                        .collect()
+                }
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+
+    /// The LCM of the presses at which each sender first sends a high pulse to `rx_sender` is
+    /// only the answer if each keeps doing so every that many presses, and at no other press.
+    /// Check this by finding the cycle in the states of the modules upstream of each sender.
+    fn verify_lcm_assumption(
+        mut self,
+        rx_sender: &Name,
+        first_high_pulses: &[PulseRecord],
+    ) -> bool {
+        let senders: Vec<&Name> = first_high_pulses.iter().map(|r| &r.pulse.from).collect();
+        let upstreams: Vec<Vec<Name>> = senders.iter().map(|s| self.upstream_of(s)).collect();
+        let mut states: Vec<Vec<Vec<bool>>> =
+            upstreams.iter().map(|u| vec![self.state_of(u)]).collect();
+        let mut high_pulses: Vec<Vec<Int>> = vec![Vec::new(); senders.len()];
+        // If the assumption holds, the prefix of each cycle is shorter than its period
+        let presses = 2 * first_high_pulses
+            .iter()
+            .map(|r| r.at_button_press)
+            .max()
+            .unwrap_or(0);
+        for _ in 0..presses {
+            self.times_pressed += 1;
+            self.press_button();
+            for record in self.pulse_history.drain(..) {
+                let pulse = record.pulse;
+                if pulse.to == *rx_sender && pulse.kind == PulseKind::High {
+                    let sender = senders.iter().position(|s| **s == pulse.from).unwrap();
+                    high_pulses[sender].push(record.at_button_press);
+                }
+            }
+            for (states, upstream) in states.iter_mut().zip(&upstreams) {
+                states.push(self.state_of(upstream));
+            }
+        }
+        let mut holds = true;
+        for ((record, states), high_pulses) in first_high_pulses.iter().zip(states).zip(high_pulses)
+        {
+            let cycle = cycle::find_cycle_in(states);
+            debug!(
+                "{}: {:?}, high at: {:?}",
+                record.pulse.from, cycle, high_pulses
+            );
+            let presses = record.at_button_press;
+            let valid = cycle.is_some_and(|cycle| {
+                let end = (cycle.prefix + cycle.period) as Int;
+                cycle.period as Int == presses
+                    && (cycle.prefix as Int) < presses
+                    && high_pulses
+                        .iter()
+                        .take_while(|p| **p <= end)
+                        .eq([presses].iter())
+            });
+            if !valid {
+                warn!(
+                    "Invalid LCM assumption for: '{}', {:?} with high pulses at: {:?}.",
+                    record.pulse.from, cycle, high_pulses
+                );
+                holds = false;
+            }
+        }
+        holds
+    }
+
     /// Initialize all conjunctions by remembering a low pulse for each input
     fn initialize_conjunctions(mut self) -> System {
         let modules: Vec<Module> = self.modules.values().cloned().collect();
@@ -308,31 +445,85 @@ impl Solution for Problem {
         )
     }
 
-    fn solve_part_one(&self, input: Input, is_example: bool) -> Answer {
-        debug!(is_example, InputLines::from(input.clone()));
-        let mut system = System::parse(input).initialize_conjunctions();
+    /// Four counters of `size` flip-flops each, that each send a high pulse towards `rx` once
+    /// every so many presses, as in the puzzle input
+    fn generate_input(&self, rng: &mut Rng, size: usize) -> Option<Input> {
+        let bits = size.clamp(3, 12);
+        let mut lines = Vec::new();
+        let mut first_flip_flops = Vec::new();
+        for counter in ['a', 'b', 'c', 'd'] {
+            // The counter resets at its period, which has its lowest and highest bits set
+            let period = rng.range(0, (1 << (bits - 2)) - 1) << 1 | 1 | 1 << (bits - 1);
+            let (hub, inverter) = (format!("h{}", counter), format!("i{}", counter));
+            let flip_flop = |bit: usize| format!("{}{}", counter, (b'a' + bit as u8) as char);
+            let mut hub_destinations = vec![inverter.clone()];
+            for bit in 0..bits {
+                let mut destinations = Vec::new();
+                if bit + 1 < bits {
+                    destinations.push(flip_flop(bit + 1));
+                }
+                if period & (1 << bit) != 0 {
+                    destinations.push(hub.clone());
+                }
+                if bit == 0 || period & (1 << bit) == 0 {
+                    hub_destinations.push(flip_flop(bit));
+                }
+                lines.push(format!(
+                    "%{} -> {}",
+                    flip_flop(bit),
+                    destinations.join(", ")
+                ));
+            }
+            lines.push(format!("&{} -> {}", hub, hub_destinations.join(", ")));
+            lines.push(format!("&{} -> zz", inverter));
+            first_flip_flops.push(flip_flop(0));
+        }
+        lines.push("&zz -> rx".to_string());
+        rng.shuffle(&mut lines);
+        lines.insert(0, format!("broadcaster -> {}", first_flip_flops.join(", ")));
+        Some(lines.join("\n") + "\n")
+    }
+
+    fn parse_input(&self, input: Input) -> Result<(), ParseError> {
+        System::parse(input).map(|_| ())
+    }
+
+    fn solve_part_one(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        trace!("{}", input);
+        let mut system = System::parse(input)?.initialize_conjunctions();
         system.press_button_repeatedly(1000);
-        system.high_pulses * system.low_pulses
+        Ok(system.high_pulses * system.low_pulses)
     }
 
-    fn solve_part_two(&self, input: Input, _is_example: bool) -> Answer {
-        let mut system = System::parse(input).initialize_conjunctions();
+    fn solve_part_two(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let mut system = System::parse(input.clone())?.initialize_conjunctions();
         system.press_button_repeatedly(1000);
-        assert!(system.rx_senders.len() == 1);
-        let rx_sender = system.rx_senders.first().unwrap().clone();
+        let [rx_sender] = &system.rx_senders[..] else {
+            return Err(ParseError::of_whole_input(
+                "a single module that sends to 'rx'",
+            ));
+        };
+        let rx_sender = rx_sender.clone();
         let rx_sender_senders: Vec<_> = system
             .modules
             .clone()
             .into_values()
             .filter(|m| m.destinations.contains(&rx_sender))
             .collect();
-        dbg!(&rx_sender_senders);
+        debug!("{:#?}", rx_sender_senders);
+        let counters = || ParseError::of_whole_input("counters that send high pulses towards 'rx'");
+        if rx_sender_senders.is_empty() {
+            return Err(counters());
+        }
         let mut high_pulses_to_rx_sender: Vec<PulseRecord> = Vec::new();
         while !rx_sender_senders.iter().all(|m| {
             high_pulses_to_rx_sender
                 .iter()
                 .any(|p| p.pulse.from == m.name)
         }) {
+            if system.times_pressed >= MAX_PRESSES {
+                return Err(counters());
+            }
             system.times_pressed += 1;
             system.press_button();
             high_pulses_to_rx_sender.extend(
@@ -355,13 +546,21 @@ impl Solution for Problem {
             }
         }
 
-        let mut solution = 1;
-        for record in &lowest_high_pulses_to_rx_sender {
-            solution = lcm(solution, record.at_button_press as u64);
+        let solution = math::lcm_of(
+            lowest_high_pulses_to_rx_sender
+                .iter()
+                .map(|record| record.at_button_press as u64),
+        )
+        .ok_or_else(counters)?;
+
+        debug!("{:#?}", lowest_high_pulses_to_rx_sender);
+        if !System::parse(input)?
+            .initialize_conjunctions()
+            .verify_lcm_assumption(&rx_sender, &lowest_high_pulses_to_rx_sender)
+        {
+            return Err(counters());
         }
 
-        dbg!(lowest_high_pulses_to_rx_sender);
-
-        solution as Answer
+        Ok(solution as Answer)
     }
 }
@@ -1,10 +1,13 @@
 use crate::grid::*;
 use crate::*;
+use std::collections::hash_map::Entry;
 
 type GardenMap = Map<Terrain>;
 type Visited = HashMap<Point, Distance>;
 type Distance = Int;
 
+const STEPS: Distance = 26501365;
+
 define_convertable_enum! {
     Terrain {
         Start => 'S',
@@ -20,64 +23,71 @@ struct Garden {
 }
 
 impl Parse for Garden {
-    fn parse(input: Input) -> Self {
-        let map: GardenMap = GardenMap {
-            grid: input
-                .lines()
-                .map(|line| {
-                    line.to_string()
-                        .chars()
-                        .map(Terrain::from_char)
-                        .collect::<Vec<_>>()
-                })
-                .collect(),
-        };
-        Garden {
-            map: map.clone(),
-            visited: Visited::new(),
+    fn parse(input: Input) -> Result<Self, ParseError> {
+        let map: GardenMap = InputLines::from(input).try_into_map()?;
+        if map.find(|t| *t == Terrain::Start).is_empty() {
+            return Err(ParseError::of_whole_input("a start"));
         }
+        Ok(Garden {
+            map,
+            visited: Visited::new(),
+        })
     }
 }
 
 impl Debug for GardenMap {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let map = self
-            .iter()
-            .map(|v| v.iter().map(|t| Terrain::to_char(t)).collect::<String>())
-            .collect::<Vec<_>>()
-            .join("\n");
-        write!(f, "\n{}", map)
+        write!(f, "\n{}", self)
     }
 }
 
-impl Garden {
-    fn run_breadth_first_search(mut self) -> Garden {
-        let mut queue: Queue<Point> = Queue::new();
-        let root = *self.map.find(|t| *t == Terrain::Start).first().unwrap();
-        queue.push_back(root);
-        self.visited.insert(root, 0);
-        while !queue.is_empty() {
-            let current = queue.pop_front().unwrap();
-            let distance = *self.visited.get(&current).unwrap();
-            for node in self.map.get_adjacent(&current) {
-                let terrain = self.map.point_get(&node).unwrap();
-                if *terrain == Terrain::Start || *terrain == Terrain::GardenPlot {
-                    if !self.visited.contains_key(&node) {
-                        self.visited.insert(node, distance + 1);
-                        queue.push_back(node);
-                    }
+/// Breadth-first search from `start`, returning the distance to every garden plot that can be
+/// reached within `max_distance` steps, on a bounded, wrapping or infinite map
+fn find_distances(map: &impl Lookup<Terrain>, start: Point, max_distance: Distance) -> Visited {
+    let mut visited = Visited::new();
+    let mut queue: Queue<Point> = Queue::new();
+    queue.push_back(start);
+    visited.insert(start, 0);
+    while let Some(current) = queue.pop_front() {
+        let distance = visited[&current];
+        if distance == max_distance {
+            continue;
+        }
+        for node in map.get_adjacent(&current) {
+            let terrain = map.point_get(&node).unwrap();
+            if *terrain == Terrain::Start || *terrain == Terrain::GardenPlot {
+                if let Entry::Vacant(e) = visited.entry(node) {
+                    e.insert(distance + 1);
+                    queue.push_back(node);
                 }
             }
         }
+    }
+    visited
+}
+
+/// Count the garden plots that can be reached in exactly `distance` steps, by stepping back and
+/// forth on any plot that can be reached in fewer steps of the same parity
+fn count_possible_locations(visited: &Visited, distance: Distance) -> Int {
+    let parity = distance % 2;
+    visited
+        .values()
+        .filter(|&&d| d % 2 == parity && d <= distance)
+        .count() as Int
+}
+
+impl Garden {
+    fn find_start(&self) -> Point {
+        *self.map.find(|t| *t == Terrain::Start).first().unwrap()
+    }
+
+    fn run_breadth_first_search(mut self) -> Garden {
+        self.visited = find_distances(&self.map, self.find_start(), Distance::MAX);
         self
     }
 
     fn count_possible_locations(&self, distance: Distance) -> Int {
-        let parity = distance % 2;
-        self.visited
-            .iter()
-            .filter(|(_, &d)| d % 2 == parity && d <= distance)
-            .count() as Int
+        count_possible_locations(&self.visited, distance)
     }
 }
 
@@ -114,38 +124,56 @@ impl Solution for Problem {
             ...........
             ",
             Expect::PartOne(16),
+            steps = 6,
         )
     }
 
-    fn solve_part_one(&self, input: Input, is_example: bool) -> Answer {
-        let garden = Garden::parse(input).run_breadth_first_search();
-        let steps = if is_example { 6 } else { 64 };
-        debug!(is_example, garden);
-        garden.count_possible_locations(steps)
-    }
-
-    fn solve_part_two(&self, input: Input, _is_example: bool) -> Answer {
-        let garden = Garden::parse(input).run_breadth_first_search();
-        // First, using breadth-first search on the Garden we found all distances
-        // Then, we use the explanation given at https://github.com/villuna/aoc23/wiki/A-Geometric-solution-to-advent-of-code-2023,-day-21 to solve (not mine)
-        let visited = garden.visited;
-        let even_corners = visited
-            .values()
-            .filter(|v| **v % 2 == 0 && **v > 65)
-            .count();
-        let odd_corners = visited
-            .values()
-            .filter(|v| **v % 2 == 1 && **v > 65)
-            .count();
-
-        let even_full = visited.values().filter(|v| **v % 2 == 0).count();
-        let odd_full = visited.values().filter(|v| **v % 2 == 1).count();
-
-        let n = ((26501365 - (garden.map.get_rows() / 2)) / garden.map.get_rows()) as usize;
-        assert_eq!(n, 202300);
-
-        let solution = (n + 1).pow(2) * odd_full + n.pow(2) * even_full - (n + 1) * odd_corners
-            + n * even_corners;
-        solution as Answer
+    fn parse_input(&self, input: Input) -> Result<(), ParseError> {
+        Garden::parse(input).map(|_| ())
+    }
+
+    fn solve_part_one(&self, input: Input, context: &Context) -> Result<Answer, ParseError> {
+        let garden = Garden::parse(input)?.run_breadth_first_search();
+        let steps = context.get_or("steps", 64);
+        debug!("{:?}", garden);
+        Ok(garden.count_possible_locations(steps))
+    }
+
+    fn solve_part_two(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let garden = Garden::parse(input)?;
+        let start = garden.find_start();
+        // The start row and column are clear of rocks, so each time the number of steps grows by
+        // the size of the map, another ring of maps is reached: the count of reachable plots grows
+        // quadratically. Sample it at three sizes on the wrapping map, and extrapolate.
+        let size = garden.map.get_rows() as Distance;
+        let remainder = STEPS % size;
+        let visited = find_distances(&garden.map.wrapping(), start, remainder + 2 * size);
+        let samples: Vec<Int> = (0..3)
+            .map(|i| count_possible_locations(&visited, remainder + i * size))
+            .collect();
+        debug!(
+            "Plots reached after {} + i * {} steps: {:?}",
+            remainder, size, samples
+        );
+        Ok(polynomial::extrapolate_at(&samples, (STEPS / size).into()) as Answer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infinite_garden() {
+        let d21 = Problem {};
+        let garden = Garden::parse(d21.define_examples().first().unwrap().get_input()).unwrap();
+        let start = garden.find_start();
+        for (steps, expected) in [(6, 16), (10, 50), (50, 1594), (100, 6536)] {
+            let visited = find_distances(&garden.map.wrapping(), start, steps);
+            test!(expected, count_possible_locations(&visited, steps));
+        }
+        let surrounded_by_rocks = SparseMap::from_map(&garden.map, Terrain::Rock);
+        let visited = find_distances(&surrounded_by_rocks, start, 6);
+        test!(16, count_possible_locations(&visited, 6));
     }
 }
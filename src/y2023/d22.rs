@@ -1,25 +1,14 @@
 use std::cmp::{max, min};
+use std::collections::HashSet;
 
-use crate::line_segment::LineSegment;
+use crate::grid::Point3;
+use crate::line_segment::LineSegment3;
 use crate::*;
 
 type BrickID = Int;
 type Bricks = HashMap<BrickID, Brick>;
 
-#[derive(Debug)]
-struct Point {
-    x: Int,
-    y: Int,
-    z: Int,
-}
-
-impl Point {
-    fn translate(&mut self, other: &Point) {
-        self.x += other.x;
-        self.y += other.y;
-        self.z += other.z;
-    }
-}
+type Point = Point3<Int>;
 
 struct Brick {
     id: BrickID,
@@ -29,12 +18,6 @@ struct Brick {
     supports: Vec<BrickID>,
 }
 
-impl Parse for Brick {
-    fn parse(input: Input) -> Self {
-        Brick::parse_result(&input).unwrap().1
-    }
-}
-
 impl Debug for Brick {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -53,26 +36,19 @@ impl Debug for Brick {
 }
 
 impl Brick {
-    fn parse_bricks(input: Input) -> Bricks {
-        input
-            .lines()
-            .enumerate()
-            .map(|(i, l)| {
-                (
-                    i as BrickID,
-                    Brick::parse(l.to_string()).assign_id(i as BrickID),
-                )
-            })
-            .collect()
+    fn parse_bricks(input: Input) -> Result<Bricks, ParseError> {
+        Ok(
+            parsing::parse_all(&input, parsing::lines(Brick::parse_result))?
+                .into_iter()
+                .enumerate()
+                .map(|(i, brick)| (i as BrickID, brick.assign_id(i as BrickID)))
+                .collect(),
+        )
     }
 
     fn parse_result(input: &str) -> IResult<&str, Brick> {
         let parse_point = |input| {
-            let (rest, (x, y, z)) = tuple((
-                terminated(parse_num, tag(",")),
-                terminated(parse_num, tag(",")),
-                parse_num,
-            ))(input)?;
+            let (rest, (x, y, z)) = parsing::triple(",")(input)?;
             Ok((rest, Point { x, y, z }))
         };
         let (rest, head) = parse_point(input)?;
@@ -91,10 +67,9 @@ impl Brick {
     }
 
     fn intersects_in_xy_plane(&self, other: &Brick) -> bool {
-        line_segment::intersects(
-            &LineSegment::new(self.head.x, self.head.y, self.tail.x, self.tail.y),
-            &LineSegment::new(other.head.x, other.head.y, other.tail.x, other.tail.y),
-        )
+        LineSegment3::new(self.head, self.tail)
+            .projected_xy()
+            .intersects(&LineSegment3::new(other.head, other.tail).projected_xy())
     }
 
     fn assign_id(mut self, id: BrickID) -> Brick {
@@ -127,16 +102,12 @@ impl Brick {
     }
 
     fn translate(&mut self, point: Point) {
-        self.head.translate(&point);
-        self.tail.translate(&point);
+        self.head += point;
+        self.tail += point;
     }
 
     fn fall_distance(&mut self, distance: Int) {
-        self.translate(Point {
-            x: 0,
-            y: 0,
-            z: -distance,
-        });
+        self.translate(Point::new(0, 0, -distance));
     }
 
     /// Fall until !self.is_falling()
@@ -221,13 +192,50 @@ impl Solution for Problem {
         )
     }
 
-    fn solve_part_one(&self, input: Input, is_example: bool) -> Answer {
-        let bricks = let_fall(Brick::parse_bricks(input));
-        if is_example {
-            let z_sorted_bricks = sort_bricks(&bricks);
-            debug!(is_example, z_sorted_bricks);
+    /// `size` bricks of up to 4 cubes in a 10 by 10 column, that do not overlap
+    fn generate_input(&self, rng: &mut Rng, size: usize) -> Option<Input> {
+        let mut occupied: HashSet<(Int, Int, Int)> = HashSet::new();
+        let mut input = String::new();
+        let (bricks, height) = (size.max(1), (2 * size).max(1) as Int);
+        let mut placed = 0;
+        while placed < bricks {
+            let mut extent = [0; 3];
+            extent[rng.below(3)] = rng.range(0, 3);
+            let head = (
+                rng.range(0, 9 - extent[0]),
+                rng.range(0, 9 - extent[1]),
+                rng.range(1, height),
+            );
+            let cubes: Vec<(Int, Int, Int)> = (0..=extent.iter().sum())
+                .map(|i| {
+                    let step = |axis: usize| if extent[axis] > 0 { i } else { 0 };
+                    (head.0 + step(0), head.1 + step(1), head.2 + step(2))
+                })
+                .collect();
+            if cubes.iter().any(|cube| occupied.contains(cube)) {
+                continue;
+            }
+            occupied.extend(cubes.iter().copied());
+            let tail = cubes.last().unwrap();
+            input += &format!(
+                "{},{},{}~{},{},{}\n",
+                head.0, head.1, head.2, tail.0, tail.1, tail.2
+            );
+            placed += 1;
+        }
+        Some(input)
+    }
+
+    fn parse_input(&self, input: Input) -> Result<(), ParseError> {
+        Brick::parse_bricks(input).map(|_| ())
+    }
+
+    fn solve_part_one(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
+        let bricks = let_fall(Brick::parse_bricks(input)?);
+        if log_enabled!(Level::Debug) {
+            debug!("{:#?}", sort_bricks(&bricks));
         }
-        bricks
+        Ok(bricks
             .values()
             .filter(|b| {
                 bricks
@@ -235,14 +243,14 @@ impl Solution for Problem {
                     .filter(|o| o.supported_by.contains(&b.id))
                     .all(|o| o.supported_by.len() > 1)
             })
-            .count() as Answer
+            .count() as Answer)
     }
 
-    fn solve_part_two(&self, input: Input, is_example: bool) -> Answer {
+    fn solve_part_two(&self, input: Input, _context: &Context) -> Result<Answer, ParseError> {
         // Breadth-first search solution where we travel upwards from any brick that we
         // remove, to see if those supported bricks have lost all of their support, which we sum,
         // for each brick
-        let bricks = let_fall(Brick::parse_bricks(input));
+        let bricks = let_fall(Brick::parse_bricks(input)?);
         let z_sorted_bricks = sort_bricks(&bricks);
         let mut sum = 0;
         for brick in z_sorted_bricks {
@@ -263,8 +271,7 @@ impl Solution for Problem {
                 }
             }
             fallen_bricks.remove(0);
-            debug!(
-                is_example,
+            trace!(
                 "{}: {} fallen: {:?}",
                 brick.id,
                 fallen_bricks.len(),
@@ -272,6 +279,6 @@ impl Solution for Problem {
             );
             sum += fallen_bricks.len();
         }
-        sum as Answer
+        Ok(sum as Answer)
     }
 }